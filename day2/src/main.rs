@@ -1,168 +1,115 @@
+extern crate intcode;
+
+use intcode::symbolic;
+use intcode::{InputSearch, IntcodeProgram, ProgramLoader};
 use std::env;
-use std::fs::File;
-use std::io::prelude::*;
+use std::process;
 use std::vec::Vec;
 
-#[derive(Debug)]
-struct IntcodeProgram {
-    data: Vec<u32>,
-}
-
-impl IntcodeProgram {
-    /**
-     * Executes an Intcode program.
-     */
-    fn run(&mut self) {
-        // initialize instruction pointer to 0
-        let mut ip: usize = 0;
-        loop {
-            if self.data[ip] == 99 {
-                // break on exit opcode
-                break;
-            }
-
-            // extract addresses from current instruction
-            let op_l = self.data[ip + 1] as usize;
-            let op_r = self.data[ip + 2] as usize;
-            let addr = self.data[ip + 3] as usize;
-
-            if self.data[ip] == 1 {
-                // add values of left and right parameters
-                // and store sum at addr
-                let sum = self.data[op_l] + self.data[op_r];
-                self.data[addr] = sum;
-            } else if self.data[ip] == 2 {
-                // multiply values of left and right parameters
-                // and store product at addr
-                let prod = self.data[op_l] * self.data[op_r];
-                self.data[addr] = prod;
-            }
-
-            // advance to next instruction
-            ip += 4;
-        }
-    }
-}
-
 /**
- * Constructs an IntcodeProgram object from the given program string.
+ * Reads the first `len` values from the memory of the given program.
  */
-fn build_intcode_program(prg: String) -> IntcodeProgram {
-    // split program into vector of values
-    let values: Vec<_> = prg.split(',').collect();
-    let mut data: Vec<u32> = Vec::<u32>::new();
-    for value in values {
-        // parse value strings as 32-bit unsigned ints
-        // and push to program data vector
-        let v = value.parse::<u32>().unwrap();
-        data.push(v);
-    }
-
-    // create IntcodeProgram object with parsed program data
-    IntcodeProgram { data }
+fn read_memory(prg: &IntcodeProgram, len: usize) -> Vec<i64> {
+    (0..len).map(|i| prg.get_value(i)).collect()
 }
 
 /**
  * Tests the given program against the given starting and ending conditions
- *  to ensure proper functionality of the build_intcode_program() and
+ *  to ensure proper functionality of the IntcodeProgram::new() and
  *  IntcodeProgram::run() functions.
  */
-fn test_intcode_program(prg: String, initial_state: &[u32], final_state: &[u32]) {
-    let mut test_prg = build_intcode_program(prg);
-    println!("Test program: {:?}", test_prg);
+fn test_intcode_program(prg: &str, initial_state: &[i64], final_state: &[i64]) {
+    let mut test_prg = IntcodeProgram::new(prg, vec![]).unwrap();
+    println!("Test program: {}", prg);
 
     println!("\tVerifying initial program data...");
-    assert_eq!(test_prg.data, initial_state);
+    assert_eq!(read_memory(&test_prg, initial_state.len()), initial_state);
 
     println!("\tRunning program...");
-    test_prg.run();
+    test_prg.run_to_halt().unwrap();
 
     println!("\tVerifying final program state...");
-    assert_eq!(test_prg.data, final_state);
+    assert_eq!(read_memory(&test_prg, final_state.len()), final_state);
 
     println!("\tSuccess!");
 }
 
 /**
- * Finds the noun and verb pair (using brute force) that produces 19690720 when
- *  the problem input is executed.
+ * Finds the noun and verb pair that produces 19690720 when the problem input
+ *  is executed. The pair is solved for directly from the closed form of
+ *  position 0, falling back to searching every pair on all cores when the
+ *  program cannot be executed symbolically.
  */
-fn find_input(prg_data: Vec<u32>) -> (u32, u32) {
-    for i in 0..100 {
-        for j in 0..100 {
-            // make a new program object with the given data
-            let mut temp_prg = IntcodeProgram {
-                data: prg_data.to_vec(),
-            };
-
-            // set i as noun and j as verb and run program
-            temp_prg.data[1] = i;
-            temp_prg.data[2] = j;
-            temp_prg.run();
-
-            if temp_prg.data[0] == 19690720 {
-                // if value is correct, return noun-verb pair
-                return (i, j);
-            }
+fn find_input(image: &[i64]) -> Option<(i64, i64)> {
+    let pair = match symbolic::closed_form(image, &[1, 2], 0) {
+        Ok(form) => {
+            println!(
+                "Position 0 as a function of noun [1] and verb [2]: {}",
+                form
+            );
+            form.solve(19690720, &[0..100, 0..100])
         }
-    }
-
-    // pair not found
-    (0, 0)
+        Err(err) => {
+            println!("Falling back to brute force: {}", err);
+            InputSearch::new(0, 19690720)
+                .input(1, 0..100)
+                .input(2, 0..100)
+                .find(image)
+                .unwrap_or_else(|err| {
+                    eprintln!("Problem searching noun-verb pairs: {}", err);
+                    process::exit(1);
+                })
+        }
+    };
+    pair.map(|pair| (pair[0], pair[1]))
 }
 
 fn main() {
     // run test programs
     test_intcode_program(
-        "1,9,10,3,2,3,11,0,99,30,40,50".to_owned(),
+        "1,9,10,3,2,3,11,0,99,30,40,50",
         &[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50],
         &[3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50],
     );
+    test_intcode_program("1,0,0,0,99", &[1, 0, 0, 0, 99], &[2, 0, 0, 0, 99]);
+    test_intcode_program("2,3,0,3,99", &[2, 3, 0, 3, 99], &[2, 3, 0, 6, 99]);
     test_intcode_program(
-        "1,0,0,0,99".to_owned(),
-        &[1, 0, 0, 0, 99],
-        &[2, 0, 0, 0, 99],
-    );
-    test_intcode_program(
-        "2,3,0,3,99".to_owned(),
-        &[2, 3, 0, 3, 99],
-        &[2, 3, 0, 6, 99],
-    );
-    test_intcode_program(
-        "2,4,4,5,99,0".to_owned(),
+        "2,4,4,5,99,0",
         &[2, 4, 4, 5, 99, 0],
         &[2, 4, 4, 5, 99, 9801],
     );
     test_intcode_program(
-        "1,1,1,4,99,5,6,0,99".to_owned(),
+        "1,1,1,4,99,5,6,0,99",
         &[1, 1, 1, 4, 99, 5, 6, 0, 99],
         &[30, 1, 1, 4, 2, 5, 6, 0, 99],
     );
 
     // read problem input file
     let args: Vec<String> = env::args().collect();
-    let mut f = File::open(&args[1]).expect("File not found!");
-    let mut prg = String::new();
-    f.read_to_string(&mut prg)
-        .expect("Something went wrong while reading the file!");
-
-    // build and run Intcode program from problem input
-    let mut int_prg = build_intcode_program(prg);
+    let image = ProgramLoader::new().load(&args[1]).unwrap_or_else(|err| {
+        eprintln!("Problem loading Intcode program from input: {}", err);
+        process::exit(1);
+    });
 
     // part two
-    let (noun, verb) = find_input(int_prg.data.to_vec());
-    println!(
-        "Noun-verb pair that produces 19690720: ({}, {})",
-        noun, verb
-    );
-    println!("100 * noun + verb: {}", (100 * noun) + verb);
+    match find_input(&image) {
+        Some((noun, verb)) => {
+            println!(
+                "Noun-verb pair that produces 19690720: ({}, {})",
+                noun, verb
+            );
+            println!("100 * noun + verb: {}", (100 * noun) + verb);
+        }
+        None => println!("No noun-verb pair produces 19690720"),
+    }
 
     // part one
-    int_prg.data[1] = 12;
-    int_prg.data[2] = 2;
-    int_prg.run();
+    let mut int_prg = IntcodeProgram::from_image(&image, vec![]);
+    int_prg.set_value(1, 12);
+    int_prg.set_value(2, 2);
+    int_prg.run_to_halt().unwrap();
     println!(
         "Value at position 0 with noun-verb pair (12, 2): {}",
-        int_prg.data[0]
+        int_prg.get_value(0)
     );
 }
@@ -0,0 +1,130 @@
+use intcode::{run_self_test, Check, SelfTestError};
+use std::vec::Vec;
+
+/// Outcome of a diagnostic run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiagnosticReport {
+    /// Last value output by the program
+    pub code: i64,
+    /// Number of test outputs before the diagnostic code
+    pub checks: usize,
+    /// Test outputs that were not zero, with the address that output them
+    pub failures: Vec<Check>,
+}
+
+impl DiagnosticReport {
+    /// Returns whether every test output was zero
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Runs the diagnostic program with the given system ID as its only input
+///
+/// Every output before the last is a test result that should be zero; each
+/// one that is not is reported as a failure. The last output is the
+/// diagnostic code.
+pub fn run_diagnostic(image: &[i64], system_id: i64) -> Result<DiagnosticReport, SelfTestError> {
+    let output = run_self_test(image, vec![system_id])?;
+    Ok(DiagnosticReport {
+        code: output.value,
+        checks: output.checks.len(),
+        failures: output
+            .checks
+            .into_iter()
+            .filter(|check| check.value != 0)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{IntcodeErrorKind, ProgramLoader};
+
+    /// Runs a sample program with each input and checks the code it outputs
+    fn check_sample(source: &str, cases: &[(i64, i64)]) {
+        let image = ProgramLoader::new().parse(source).unwrap();
+        for (input, expected) in cases.iter() {
+            let report = run_diagnostic(&image, *input).unwrap();
+            assert_eq!(report.code, *expected, "{} with input {}", source, input);
+            assert!(report.passed());
+        }
+    }
+
+    #[test]
+    fn echo_input() {
+        check_sample("3,0,4,0,99", &[(1, 1), (-7, -7)]);
+    }
+
+    #[test]
+    fn equals_and_less_than() {
+        // position and immediate mode: 1 if input = 8, else 0
+        check_sample("3,9,8,9,10,9,4,9,99,-1,8", &[(7, 0), (8, 1), (9, 0)]);
+        check_sample("3,3,1108,-1,8,3,4,3,99", &[(7, 0), (8, 1), (9, 0)]);
+
+        // position and immediate mode: 1 if input < 8, else 0
+        check_sample("3,9,7,9,10,9,4,9,99,-1,8", &[(7, 1), (8, 0), (9, 0)]);
+        check_sample("3,3,1107,-1,8,3,4,3,99", &[(7, 1), (8, 0), (9, 0)]);
+    }
+
+    #[test]
+    fn jumps() {
+        // position and immediate mode: 1 if input is non-zero, else 0
+        check_sample(
+            "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9",
+            &[(0, 0), (5, 1)],
+        );
+        check_sample("3,3,1105,-1,9,1101,0,0,12,4,12,99,1", &[(0, 0), (-5, 1)]);
+    }
+
+    #[test]
+    fn compare_to_eight() {
+        // 999 if input < 8, 1000 if input = 8, 1001 if input > 8
+        check_sample(
+            "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99",
+            &[(7, 999), (8, 1000), (9, 1001)],
+        );
+    }
+
+    #[test]
+    fn flag_failed_checks() {
+        let image = ProgramLoader::new()
+            .parse("3,13,104,0,4,13,104,0,104,0,4,13,99,0")
+            .unwrap();
+        let report = run_diagnostic(&image, 0).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.checks, 4);
+
+        let report = run_diagnostic(&image, 3).unwrap();
+        assert!(!report.passed());
+        assert_eq!(report.code, 3);
+        assert_eq!(
+            report.failures,
+            vec![Check {
+                address: 4,
+                value: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn diagnostic_errors() {
+        let image = ProgramLoader::new().parse("3,0,3,0,99").unwrap();
+        match run_diagnostic(&image, 1) {
+            Err(SelfTestError::Program(err)) => {
+                assert_eq!(err.kind, IntcodeErrorKind::InputExhausted)
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let image = ProgramLoader::new().parse("1002,4,3,4,33").unwrap();
+        assert_eq!(run_diagnostic(&image, 1), Err(SelfTestError::NoOutput));
+
+        let image = ProgramLoader::new().parse("3,0,42").unwrap();
+        match run_diagnostic(&image, 1) {
+            Err(SelfTestError::Program(err)) => assert_eq!(err.instruction_pointer, 2),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
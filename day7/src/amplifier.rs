@@ -0,0 +1,180 @@
+use intcode::{IntcodeError, IntcodeProgram};
+use std::error::Error;
+use std::fmt;
+use std::vec::Vec;
+
+/// How the amplifiers of a circuit are wired together
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Each amplifier feeds the next, and the last one feeds the thrusters
+    Linear,
+    /// The last amplifier also feeds the first, until the last one halts
+    Feedback,
+}
+
+/// Error raised while running an amplifier circuit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircuitError {
+    /// Program of the given amplifier failed
+    Program {
+        amplifier: usize,
+        error: IntcodeError,
+    },
+    /// Given amplifier stopped without producing a signal
+    NoOutput { amplifier: usize },
+    /// Number of phase settings does not match the number of amplifiers
+    PhaseCount { amplifiers: usize, phases: usize },
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircuitError::Program { amplifier, error } => {
+                write!(f, "amplifier {} failed: {}", amplifier, error)
+            }
+            CircuitError::NoOutput { amplifier } => {
+                write!(f, "amplifier {} produced no output", amplifier)
+            }
+            CircuitError::PhaseCount { amplifiers, phases } => {
+                write!(
+                    f,
+                    "{} phase settings given for {} amplifiers",
+                    phases, amplifiers
+                )
+            }
+        }
+    }
+}
+
+impl Error for CircuitError {}
+
+/// Chain of Intcode amplifiers, each started with its own phase setting
+#[derive(Debug)]
+pub struct AmplifierCircuit {
+    amplifiers: Vec<IntcodeProgram>,
+    topology: Topology,
+}
+
+impl AmplifierCircuit {
+    /// Wires the given programs together in order, feeding each one the
+    /// phase setting at the same position
+    ///
+    /// Fails if there is not exactly one phase setting per program.
+    pub fn new(
+        mut amplifiers: Vec<IntcodeProgram>,
+        phases: &[i64],
+        topology: Topology,
+    ) -> Result<AmplifierCircuit, CircuitError> {
+        if amplifiers.len() != phases.len() {
+            return Err(CircuitError::PhaseCount {
+                amplifiers: amplifiers.len(),
+                phases: phases.len(),
+            });
+        }
+        for (amp, phase) in amplifiers.iter_mut().zip(phases.iter()) {
+            amp.feed(*phase);
+        }
+        Ok(AmplifierCircuit {
+            amplifiers,
+            topology,
+        })
+    }
+
+    /// Wires one fresh copy of the image per phase setting
+    pub fn from_image(image: &[i64], phases: &[i64], topology: Topology) -> AmplifierCircuit {
+        let amplifiers = phases
+            .iter()
+            .map(|phase| IntcodeProgram::from_image(image, vec![*phase]))
+            .collect();
+        AmplifierCircuit {
+            amplifiers,
+            topology,
+        }
+    }
+
+    /// Sends the input signal to the first amplifier and returns the last
+    /// signal sent to the thrusters
+    ///
+    /// Every value an amplifier outputs is passed on to the next one. With
+    /// feedback, the signals go around the loop until the last amplifier halts.
+    pub fn run(&mut self, signal: i64) -> Result<i64, CircuitError> {
+        let mut signals = vec![signal];
+        loop {
+            for (i, amp) in self.amplifiers.iter_mut().enumerate() {
+                amp.input.extend(signals);
+                amp.run().map_err(|error| CircuitError::Program {
+                    amplifier: i,
+                    error,
+                })?;
+                signals = amp.take_output();
+                if signals.is_empty() {
+                    return Err(CircuitError::NoOutput { amplifier: i });
+                }
+            }
+
+            let done = match self.amplifiers.last() {
+                Some(last) => self.topology == Topology::Linear || last.is_halted(),
+                None => true,
+            };
+            if done {
+                return Ok(signals[signals.len() - 1]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::ProgramLoader;
+
+    fn load(source: &str) -> Vec<i64> {
+        ProgramLoader::new().parse(source).unwrap()
+    }
+
+    #[test]
+    fn linear_samples() {
+        let image = load("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0");
+        let mut circuit = AmplifierCircuit::from_image(&image, &[4, 3, 2, 1, 0], Topology::Linear);
+        assert_eq!(circuit.run(0), Ok(43210));
+
+        let image =
+            load("3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0");
+        let mut circuit = AmplifierCircuit::from_image(&image, &[0, 1, 2, 3, 4], Topology::Linear);
+        assert_eq!(circuit.run(0), Ok(54321));
+    }
+
+    #[test]
+    fn feedback_sample() {
+        let image = load(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        );
+        let mut circuit =
+            AmplifierCircuit::from_image(&image, &[9, 8, 7, 6, 5], Topology::Feedback);
+        assert_eq!(circuit.run(0), Ok(139629729));
+    }
+
+    #[test]
+    fn circuit_errors() {
+        // reads its phase and signal, then halts without output
+        let image = load("3,0,3,0,99");
+        let mut circuit = AmplifierCircuit::from_image(&image, &[1, 2], Topology::Linear);
+        assert_eq!(circuit.run(0), Err(CircuitError::NoOutput { amplifier: 0 }));
+
+        let image = load("3,0,3,0,42");
+        let mut circuit = AmplifierCircuit::from_image(&image, &[1], Topology::Feedback);
+        match circuit.run(0) {
+            Err(CircuitError::Program { amplifier: 0, .. }) => (),
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let amplifiers = vec![IntcodeProgram::from_image(&image, vec![])];
+        match AmplifierCircuit::new(amplifiers, &[1, 2], Topology::Linear) {
+            Err(CircuitError::PhaseCount {
+                amplifiers: 1,
+                phases: 2,
+            }) => (),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
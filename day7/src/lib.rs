@@ -1 +1,3 @@
-pub mod intcode;
\ No newline at end of file
+pub mod amplifier;
+pub mod permutations;
+pub mod search;
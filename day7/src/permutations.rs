@@ -0,0 +1,109 @@
+use std::vec::Vec;
+
+/// Lazy iterator over every ordering of a slice of values
+///
+/// Orderings are generated one at a time with the Johnson-Trotter algorithm,
+/// so consecutive permutations differ by a single swap of adjacent values.
+/// Values are permuted by position; equal values are not deduplicated.
+#[derive(Clone, Debug)]
+pub struct Permutations<'a, T> {
+    values: &'a [T],
+    /// Positions into `values` in their current order
+    order: Vec<usize>,
+    /// Direction each position is moving in, indexed by position; true for right
+    directions: Vec<bool>,
+    done: bool,
+}
+
+/// Returns an iterator over every permutation of the given values, starting
+/// with the values in their original order
+pub fn permutations<T: Clone>(values: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        values,
+        order: (0..values.len()).collect(),
+        directions: vec![false; values.len()],
+        done: false,
+    }
+}
+
+impl<'a, T> Permutations<'a, T> {
+    /// Finds the index in `order` of the largest mobile element, i.e. the
+    /// largest one pointing at a smaller neighbor
+    fn largest_mobile(&self) -> Option<usize> {
+        let n = self.order.len();
+        (0..n)
+            .filter(|&i| {
+                let e = self.order[i];
+                if self.directions[e] {
+                    i + 1 < n && self.order[i + 1] < e
+                } else {
+                    i > 0 && self.order[i - 1] < e
+                }
+            })
+            .max_by_key(|&i| self.order[i])
+    }
+
+    /// Moves on to the next ordering, or marks the iterator as done
+    fn advance(&mut self) {
+        let i = match self.largest_mobile() {
+            Some(i) => i,
+            None => {
+                self.done = true;
+                return;
+            }
+        };
+
+        // swap with the neighbor it points at
+        let e = self.order[i];
+        let j = if self.directions[e] { i + 1 } else { i - 1 };
+        self.order.swap(i, j);
+
+        // reverse the direction of every larger element
+        for d in self.directions[e + 1..].iter_mut() {
+            *d = !*d;
+        }
+    }
+}
+
+impl<'a, T: Clone> Iterator for Permutations<'a, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.done {
+            return None;
+        }
+        let permutation = self.order.iter().map(|&i| self.values[i].clone()).collect();
+        self.advance();
+        Some(permutation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn johnson_trotter_order() {
+        let all: Vec<Vec<char>> = permutations(&['a', 'b', 'c']).collect();
+        let expected = ["abc", "acb", "cab", "cba", "bca", "bac"];
+        let expected: Vec<Vec<char>> = expected.iter().map(|p| p.chars().collect()).collect();
+        assert_eq!(all, expected);
+    }
+
+    #[test]
+    fn every_permutation_once() {
+        let values = [5, 6, 7, 8, 9, 10];
+        let all: HashSet<Vec<i64>> = permutations(&values).collect();
+        assert_eq!(all.len(), 720);
+        assert_eq!(permutations(&values).count(), 720);
+    }
+
+    #[test]
+    fn small_inputs() {
+        assert_eq!(permutations::<i64>(&[]).collect::<Vec<_>>(), vec![vec![]]);
+        assert_eq!(permutations(&[1]).collect::<Vec<_>>(), vec![vec![1]]);
+        // repeated values are permuted by position
+        assert_eq!(permutations(&[2, 2]).count(), 2);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::amplifier::{AmplifierCircuit, CircuitError, Topology};
+use crate::permutations::permutations;
+use std::sync::Mutex;
+use std::thread;
+use std::vec::Vec;
+
+/// Thruster signal reached with one ordering of the phase settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseResult {
+    /// Position of the ordering in the permutation sequence
+    pub index: usize,
+    pub phases: Vec<i64>,
+    pub signal: i64,
+}
+
+/// Result of one ordering, or its position, phases and error if it failed
+type Attempt = Result<PhaseResult, (usize, Vec<i64>, CircuitError)>;
+
+/// Runs the circuit with every ordering of the phase settings, spread across
+/// the given number of threads, and returns the results ranked by signal
+///
+/// Ties are broken by permutation order, so the ranking (and the best result
+/// at its head) does not depend on thread scheduling. If any ordering fails,
+/// the error of the earliest failing ordering is returned.
+pub fn rank_phases(
+    image: &[i64],
+    settings: &[i64],
+    topology: Topology,
+    threads: usize,
+) -> Result<Vec<PhaseResult>, (Vec<i64>, CircuitError)> {
+    // workers share a single lazy iterator, so each ordering is generated
+    // exactly once; the lock is only held while taking the next one
+    let orderings = Mutex::new(permutations(settings).enumerate());
+    let mut results: Vec<Attempt> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                let orderings = &orderings;
+                scope.spawn(move || {
+                    let mut attempts = Vec::new();
+                    loop {
+                        let next = orderings.lock().unwrap().next();
+                        let (index, phases) = match next {
+                            Some(ordering) => ordering,
+                            None => return attempts,
+                        };
+                        let mut circuit = AmplifierCircuit::from_image(image, &phases, topology);
+                        attempts.push(match circuit.run(0) {
+                            Ok(signal) => Ok(PhaseResult {
+                                index,
+                                phases,
+                                signal,
+                            }),
+                            Err(err) => Err((index, phases, err)),
+                        });
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    let index = |result: &Attempt| match result {
+        Ok(result) => result.index,
+        Err((index, _, _)) => *index,
+    };
+    results.sort_by_key(index);
+
+    let mut ranked = Vec::new();
+    for result in results {
+        match result {
+            Ok(result) => ranked.push(result),
+            Err((_, phases, err)) => return Err((phases, err)),
+        }
+    }
+    ranked.sort_by(|a, b| b.signal.cmp(&a.signal).then(a.index.cmp(&b.index)));
+    Ok(ranked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::ProgramLoader;
+
+    #[test]
+    fn best_linear_phases() {
+        let image = ProgramLoader::new()
+            .parse("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0")
+            .unwrap();
+        let ranked = rank_phases(&image, &[0, 1, 2, 3, 4], Topology::Linear, 4).unwrap();
+        assert_eq!(ranked.len(), 120);
+        assert_eq!(ranked[0].phases, vec![4, 3, 2, 1, 0]);
+        assert_eq!(ranked[0].signal, 43210);
+        assert!(ranked.windows(2).all(|w| w[0].signal >= w[1].signal));
+    }
+
+    #[test]
+    fn deterministic_ties() {
+        // outputs the signal plus the phase, so every ordering ties
+        let image = ProgramLoader::new()
+            .parse("3,11,3,12,1,11,12,11,4,11,99,0,0")
+            .unwrap();
+        let expected = rank_phases(&image, &[1, 2, 3], Topology::Linear, 1).unwrap();
+        let indices: Vec<usize> = expected.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3, 4, 5]);
+        for threads in 2..8 {
+            let ranked = rank_phases(&image, &[1, 2, 3], Topology::Linear, threads).unwrap();
+            assert_eq!(ranked, expected);
+        }
+    }
+
+    #[test]
+    fn earliest_error() {
+        // halts without output
+        let image = ProgramLoader::new().parse("3,0,3,0,99").unwrap();
+        let err = rank_phases(&image, &[7, 8], Topology::Linear, 2).unwrap_err();
+        assert_eq!(err, (vec![7, 8], CircuitError::NoOutput { amplifier: 0 }));
+    }
+}
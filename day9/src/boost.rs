@@ -0,0 +1,161 @@
+use intcode::disasm::{self, Instruction};
+use intcode::{run_self_test, SelfTestError};
+use std::fmt;
+use std::vec::Vec;
+
+/// BOOST program input selecting the self-test
+pub const TEST_MODE: i64 = 1;
+/// BOOST program input selecting sensor boost
+pub const SENSOR_BOOST_MODE: i64 = 2;
+
+/// Instruction reported as malfunctioning by the BOOST self-test
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Malfunction {
+    /// Value that was output: the opcode with its parameter modes
+    pub value: i64,
+    /// Instruction decoded from the value, if it is a valid one
+    pub instruction: Option<Instruction>,
+}
+
+impl Malfunction {
+    /// Interprets a value output by the self-test
+    pub fn new(value: i64) -> Malfunction {
+        // decode with placeholder parameters to recover the modes
+        let instruction = disasm::decode_instruction(&[value, 0, 0, 0], 0);
+        Malfunction { value, instruction }
+    }
+}
+
+/// Returns the name of a parameter mode
+fn mode_name(mode: u8) -> &'static str {
+    match mode {
+        0 => "position",
+        1 => "immediate",
+        _ => "relative",
+    }
+}
+
+impl fmt::Display for Malfunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.instruction {
+            Some(ins) => {
+                write!(f, "opcode {} ({})", ins.opcode, ins.mnemonic())?;
+                let modes: Vec<&str> = ins.operands.iter().map(|op| mode_name(op.mode())).collect();
+                if !modes.is_empty() {
+                    write!(f, " with {} mode", modes.join(", "))?;
+                }
+                Ok(())
+            }
+            None => write!(f, "unknown instruction {}", self.value),
+        }
+    }
+}
+
+/// Values output by a BOOST run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoostOutput {
+    /// Instructions reported as malfunctioning
+    pub malfunctions: Vec<Malfunction>,
+    /// Final output: the BOOST keycode in test mode, or the coordinates of
+    /// the distress signal in sensor boost mode
+    pub value: i64,
+}
+
+/// Runs the BOOST program in the given mode
+///
+/// Every output before the last names a malfunctioning instruction; the last
+/// is the keycode or coordinates.
+pub fn run_boost(image: &[i64], mode: i64) -> Result<BoostOutput, SelfTestError> {
+    let output = run_self_test(image, vec![mode])?;
+    Ok(BoostOutput {
+        malfunctions: output
+            .checks
+            .iter()
+            .map(|check| Malfunction::new(check.value))
+            .collect(),
+        value: output.value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use intcode::{IntcodeErrorKind, ProgramLoader};
+
+    #[test]
+    fn large_values() {
+        let output = run_boost(
+            &ProgramLoader::new()
+                .parse("1102,34915192,34915192,7,4,7,99,0")
+                .unwrap(),
+            TEST_MODE,
+        )
+        .unwrap();
+        assert_eq!(output.value, 1219070632396864);
+        assert!(output.malfunctions.is_empty());
+
+        let output = run_boost(
+            &ProgramLoader::new()
+                .parse("104,1125899906842624,99")
+                .unwrap(),
+            TEST_MODE,
+        )
+        .unwrap();
+        assert_eq!(output.value, 1125899906842624);
+    }
+
+    #[test]
+    fn quine() {
+        let source = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let output = run_boost(&ProgramLoader::new().parse(source).unwrap(), TEST_MODE).unwrap();
+        assert_eq!(output.value, 99);
+        assert_eq!(output.malfunctions.len(), 15);
+        assert_eq!(
+            output.malfunctions[0].to_string(),
+            "opcode 9 (arb) with immediate mode"
+        );
+    }
+
+    #[test]
+    fn name_malfunctions() {
+        let output = run_boost(
+            &ProgramLoader::new()
+                .parse("3,0,104,203,104,21101,104,42,104,5,99")
+                .unwrap(),
+            TEST_MODE,
+        )
+        .unwrap();
+        assert_eq!(output.value, 5);
+        let names: Vec<String> = output.malfunctions.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "opcode 3 (in) with relative mode",
+                "opcode 1 (add) with immediate, immediate, relative mode",
+                "unknown instruction 42",
+            ]
+        );
+        assert_eq!(Malfunction::new(99).to_string(), "opcode 99 (hlt)");
+    }
+
+    #[test]
+    fn boost_errors() {
+        assert_eq!(
+            run_boost(
+                &ProgramLoader::new().parse("3,0,99").unwrap(),
+                SENSOR_BOOST_MODE
+            ),
+            Err(SelfTestError::NoOutput)
+        );
+        match run_boost(
+            &ProgramLoader::new().parse("3,0,3,0,99").unwrap(),
+            TEST_MODE,
+        ) {
+            Err(SelfTestError::Program(err)) => {
+                assert_eq!(err.kind, IntcodeErrorKind::InputExhausted);
+                assert_eq!(err.instruction_pointer, 2);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
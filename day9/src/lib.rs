@@ -1 +1 @@
-pub mod intcode;
\ No newline at end of file
+pub mod boost;
@@ -1,20 +1,43 @@
-extern crate day9;
+extern crate intcode;
 
-use day9::intcode::IntcodeProgram;
+use day9::boost::{run_boost, BoostOutput, SENSOR_BOOST_MODE, TEST_MODE};
+use intcode::ProgramLoader;
 use std::env;
-use std::fs::File;
-use std::io::prelude::*;
+use std::process;
+use std::vec::Vec;
+
+/// Runs the BOOST program in the given mode, exiting if it fails
+fn run_mode(image: &[i64], mode: i64) -> BoostOutput {
+    run_boost(image, mode).unwrap_or_else(|err| {
+        eprintln!("Error occured during program execution: {}", err);
+        process::exit(1);
+    })
+}
 
 fn main() {
     // read in problem input
-    println!("Running problem program...");
     let args: Vec<String> = env::args().collect();
-    let mut f = File::open(&args[1]).expect("File not found!");
-    let mut data = String::new();
-    f.read_to_string(&mut data)
-        .expect("Something went wrong while reading the file!");
+    let image = ProgramLoader::new().load(&args[1]).unwrap_or_else(|err| {
+        eprintln!("Problem loading Intcode program from input: {}", err);
+        process::exit(1);
+    });
+
+    // part one: self-test, reporting any malfunctioning instructions
+    println!("Running BOOST self-test (mode {})...", TEST_MODE);
+    let test = run_mode(&image, TEST_MODE);
+    for malfunction in test.malfunctions.iter() {
+        println!(
+            "\tMalfunctioning instruction {}: {}",
+            malfunction.value, malfunction
+        );
+    }
+    if test.malfunctions.is_empty() {
+        println!("\tNo malfunctioning instructions reported");
+    }
+    println!("BOOST keycode: {}", test.value);
 
-    // create and run program for problem input
-    let mut prg = IntcodeProgram::new(&data, None).unwrap();
-    prg.run().unwrap();
+    // part two: sensor boost
+    println!("Running sensor boost (mode {})...", SENSOR_BOOST_MODE);
+    let boost = run_mode(&image, SENSOR_BOOST_MODE);
+    println!("Coordinates of the distress signal: {}", boost.value);
 }
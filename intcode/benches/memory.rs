@@ -0,0 +1,46 @@
+//! Compares memory backends and the decode cache on the day 9 BOOST program
+//! in sensor boost mode
+//!
+//! Run with `cargo bench`.
+extern crate intcode;
+
+use intcode::{DenseMemory, IntcodeProgram, Memory, ProgramLoader, SparseMemory};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 10;
+
+/// Runs the BOOST program in sensor boost mode and returns the average run time
+fn time_backend<M: Memory>(image: &[i64], decode_cache: bool) -> Duration {
+  let start = Instant::now();
+  for _ in 0..ITERATIONS {
+    let input: VecDeque<i64> = vec![2].into_iter().collect();
+    let mut prg = IntcodeProgram::with_memory(M::from_image(image), input, Vec::new());
+    if decode_cache {
+      prg.enable_decode_cache();
+    }
+    prg.run_to_halt().unwrap();
+    assert_eq!(prg.output.len(), 1);
+  }
+  start.elapsed() / ITERATIONS
+}
+
+fn main() {
+  let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../day9/input.txt");
+  let image = ProgramLoader::new().load(path).unwrap_or_else(|err| {
+    eprintln!("Problem loading {}: {}", path, err);
+    std::process::exit(1);
+  });
+
+  let sparse = time_backend::<SparseMemory>(&image, false);
+  let dense = time_backend::<DenseMemory>(&image, false);
+  let cached = time_backend::<DenseMemory>(&image, true);
+  println!("day9 part 2, average of {} runs:", ITERATIONS);
+  println!("  SparseMemory (HashMap):     {:?}", sparse);
+  println!("  DenseMemory:                {:?}", dense);
+  println!("  DenseMemory + decode cache: {:?}", cached);
+  println!(
+    "  speedup: {:.2}x",
+    sparse.as_secs_f64() / dense.as_secs_f64()
+  );
+}
@@ -0,0 +1,186 @@
+use crate::error::IntcodeError;
+use crate::memory::{DenseMemory, Memory};
+use crate::program::{IntcodeProgram, RunStatus};
+use std::collections::VecDeque;
+use std::vec::Vec;
+
+/// Why an ASCII program stopped producing output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsciiStop {
+  /// A line ending with the requested prompt was read
+  Prompt,
+  /// Program is waiting for input
+  NeedsInput,
+  /// Program reached the exit instruction
+  Halted,
+  /// Instruction budget or deadline ran out
+  BudgetExhausted,
+}
+
+/// Text and answer values emitted by an ASCII program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsciiOutput {
+  /// Complete lines of text, without their newlines
+  pub lines: Vec<String>,
+  /// Emitted values outside the ASCII range (0 to 127)
+  pub answers: Vec<i64>,
+  /// Why reading stopped
+  pub stop: AsciiStop,
+}
+
+impl AsciiOutput {
+  /// Returns the lines joined back into text
+  pub fn text(&self) -> String {
+    self
+      .lines
+      .iter()
+      .map(|line| format!("{}\n", line))
+      .collect()
+  }
+}
+
+/// Adapter for Intcode programs that talk in lines of ASCII text
+///
+/// Lines sent to the program are encoded as character codes followed by a
+/// newline. Output is decoded into lines, with values that are not ASCII kept
+/// apart as answers.
+#[derive(Debug)]
+pub struct AsciiProgram<M = DenseMemory> {
+  pub program: IntcodeProgram<VecDeque<i64>, Vec<i64>, M>,
+}
+
+impl<M: Memory> AsciiProgram<M> {
+  /// Wraps the given program
+  pub fn new(program: IntcodeProgram<VecDeque<i64>, Vec<i64>, M>) -> AsciiProgram<M> {
+    AsciiProgram { program }
+  }
+
+  /// Queues a line of input followed by a newline
+  pub fn send_line(&mut self, line: &str) {
+    self
+      .program
+      .input
+      .extend(line.bytes().map(i64::from).chain(Some(10)));
+  }
+
+  /// Runs the program until it waits for input, halts, or runs out of budget
+  pub fn read(&mut self) -> Result<AsciiOutput, IntcodeError> {
+    self.read_output(None)
+  }
+
+  /// Runs the program until a line of output ends with the given prompt, or
+  /// until it waits for input, halts, or runs out of budget
+  ///
+  /// Prompts that are not followed by a newline are returned as the last
+  /// line once the program waits for input.
+  pub fn read_until(&mut self, prompt: &str) -> Result<AsciiOutput, IntcodeError> {
+    self.read_output(Some(prompt))
+  }
+
+  /// Decodes output until the prompt is seen or the program stops
+  fn read_output(&mut self, prompt: Option<&str>) -> Result<AsciiOutput, IntcodeError> {
+    let mut lines = Vec::new();
+    let mut answers = Vec::new();
+    let mut line = String::new();
+    let at_prompt = |line: &str| prompt.is_some_and(|p| line.ends_with(p));
+
+    let stop = loop {
+      match self.program.run_until_output()? {
+        RunStatus::Output(value) => {
+          self.program.output.pop();
+          match value {
+            10 => {
+              let done = at_prompt(&line);
+              lines.push(std::mem::take(&mut line));
+              if done {
+                break AsciiStop::Prompt;
+              }
+            }
+            0..=127 => line.push(value as u8 as char),
+            _ => answers.push(value),
+          }
+        }
+        RunStatus::Running => (),
+        RunStatus::NeedsInput => break AsciiStop::NeedsInput,
+        RunStatus::Halted => break AsciiStop::Halted,
+        RunStatus::BudgetExhausted => break AsciiStop::BudgetExhausted,
+      }
+    };
+
+    if !line.is_empty() {
+      lines.push(line);
+    }
+    Ok(AsciiOutput {
+      lines,
+      answers,
+      stop,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::asm;
+
+  /// Greets, then echoes one line back and emits a non-ASCII answer
+  const ECHO: &str = "
+        out #72
+        out #105
+        out #10
+        out #63
+        out #10
+  loop: in [c]
+        out [c]
+        eq [c], #10, [t]
+        jf [t], #loop
+        out #12345
+        hlt
+  c:    db 0
+  t:    db 0
+  ";
+
+  fn echo() -> AsciiProgram {
+    let image = asm::assemble(ECHO).unwrap();
+    AsciiProgram::new(IntcodeProgram::from_image(&image, vec![]))
+  }
+
+  #[test]
+  fn lines_until_prompt() {
+    let mut prg = echo();
+    let output = prg.read_until("?").unwrap();
+    assert_eq!(output.lines, vec!["Hi", "?"]);
+    assert!(output.answers.is_empty());
+    assert_eq!(output.stop, AsciiStop::Prompt);
+    assert_eq!(output.text(), "Hi\n?\n");
+
+    prg.send_line("ok");
+    let output = prg.read().unwrap();
+    assert_eq!(output.lines, vec!["ok"]);
+    assert_eq!(output.answers, vec![12345]);
+    assert_eq!(output.stop, AsciiStop::Halted);
+  }
+
+  #[test]
+  fn read_until_input() {
+    let mut prg = echo();
+    let output = prg.read().unwrap();
+    assert_eq!(output.lines, vec!["Hi", "?"]);
+    assert_eq!(output.stop, AsciiStop::NeedsInput);
+    assert!(prg.program.output.is_empty());
+  }
+
+  #[test]
+  fn prompt_without_newline() {
+    let image = asm::assemble("out #62\nout #32\nin [9]\nout #1000\nhlt").unwrap();
+    let mut prg = AsciiProgram::new(IntcodeProgram::from_image(&image, vec![]));
+    let output = prg.read_until("> ").unwrap();
+    assert_eq!(output.lines, vec!["> "]);
+    assert_eq!(output.stop, AsciiStop::NeedsInput);
+
+    prg.send_line("");
+    let output = prg.read_until("> ").unwrap();
+    assert!(output.lines.is_empty());
+    assert_eq!(output.answers, vec![1000]);
+  }
+}
@@ -0,0 +1,516 @@
+//! Assembler for Intcode mnemonic source
+//!
+//! Each line holds an optional label, then an instruction or directive, then
+//! an optional `;` comment:
+//!
+//! ```text
+//! start:  in [x]              ; read a value
+//!         mul [x], #2, rb[1]
+//!         out rb[1]
+//!         hlt
+//! x:      db 0
+//! ```
+//!
+//! Operands are written with an explicit mode: `[addr]` for position mode,
+//! `#value` for immediate mode and `rb[offset]` for relative mode. Values are
+//! integers, labels, or a label plus or minus an integer (`x+1`).
+//!
+//! `db` places raw values in memory. `call target` and `ret` implement
+//! subroutines with the relative base as stack pointer: `call` pushes the
+//! return address to `rb[0]` and jumps, and `ret` pops it and jumps back.
+//! Programs using them should first point the relative base at free memory,
+//! e.g. `arb #stack`.
+use crate::disasm::{self, Instruction, Line, Operand};
+use crate::operation::IntcodeOperation;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::vec::Vec;
+
+/// Number of memory values taken by the `call` macro
+const CALL_LEN: usize = 9;
+/// Number of memory values taken by the `ret` macro
+const RET_LEN: usize = 5;
+
+/// Cause of a failure to assemble a line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AsmErrorKind {
+  /// Mnemonic or directive is not recognized
+  UnknownMnemonic(String),
+  /// Instruction was given the wrong number of operands
+  OperandCount { expected: usize, found: usize },
+  /// Operand or value could not be parsed
+  InvalidOperand(String),
+  /// Parameter that is written to uses immediate mode
+  ImmediateWrite,
+  /// Label is used but never defined
+  UndefinedLabel(String),
+  /// Label is defined more than once
+  DuplicateLabel(String),
+  /// Label plus offset does not fit in a 64-bit integer
+  LabelOverflow(String),
+}
+
+/// Error raised while assembling Intcode source
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmError {
+  /// Line of the source the error was found on (1-based)
+  pub line: usize,
+  /// Cause of the failure
+  pub kind: AsmErrorKind,
+}
+
+impl fmt::Display for AsmErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      AsmErrorKind::UnknownMnemonic(m) => write!(f, "unknown mnemonic {:?}", m),
+      AsmErrorKind::OperandCount { expected, found } => {
+        write!(f, "expected {} operands, found {}", expected, found)
+      }
+      AsmErrorKind::InvalidOperand(op) => write!(f, "invalid operand {:?}", op),
+      AsmErrorKind::ImmediateWrite => write!(f, "write operand in immediate mode"),
+      AsmErrorKind::UndefinedLabel(l) => write!(f, "undefined label {:?}", l),
+      AsmErrorKind::DuplicateLabel(l) => write!(f, "duplicate label {:?}", l),
+      AsmErrorKind::LabelOverflow(l) => write!(f, "offset from label {:?} overflows", l),
+    }
+  }
+}
+
+impl fmt::Display for AsmError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} on line {}", self.kind, self.line)
+  }
+}
+
+impl Error for AsmError {}
+
+/// Value that may refer to a label
+#[derive(Clone, Debug)]
+enum Expr {
+  Number(i64),
+  Label(String, i64),
+}
+
+/// Operand with its mode before labels are resolved
+#[derive(Clone, Debug)]
+struct Arg {
+  mode: u8,
+  expr: Expr,
+}
+
+/// Parsed line contents
+#[derive(Clone, Debug)]
+enum Statement {
+  Instruction(u8, Vec<Arg>),
+  Data(Vec<Expr>),
+  Call(Expr),
+  Ret,
+}
+
+impl Statement {
+  /// Returns the number of memory values the statement assembles to
+  fn len(&self) -> usize {
+    match self {
+      Statement::Instruction(_, args) => args.len() + 1,
+      Statement::Data(values) => values.len(),
+      Statement::Call(_) => CALL_LEN,
+      Statement::Ret => RET_LEN,
+    }
+  }
+}
+
+/// Returns the opcode of the given mnemonic
+fn opcode(mnemonic: &str) -> Option<u8> {
+  (1..=9)
+    .chain(Some(99))
+    .find(|&code| disasm::mnemonic(code) == Some(mnemonic))
+}
+
+/// Returns whether the text is a valid label name
+fn is_label(text: &str) -> bool {
+  let mut chars = text.chars();
+  match chars.next() {
+    Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+      chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+    _ => false,
+  }
+}
+
+/// Parses an integer, a label, or a label with an offset
+fn parse_expr(text: &str) -> Result<Expr, AsmErrorKind> {
+  let text = text.trim();
+  let invalid = || AsmErrorKind::InvalidOperand(text.to_owned());
+  if let Ok(value) = text.parse::<i64>() {
+    return Ok(Expr::Number(value));
+  }
+
+  // split off an offset after the label
+  let (name, offset) = match text.find(&['+', '-'][..]) {
+    Some(i) => {
+      let offset: i64 = text[i + 1..].trim().parse().map_err(|_| invalid())?;
+      let offset = if &text[i..=i] == "-" { -offset } else { offset };
+      (text[..i].trim(), offset)
+    }
+    None => (text, 0),
+  };
+  if !is_label(name) {
+    return Err(invalid());
+  }
+  Ok(Expr::Label(name.to_owned(), offset))
+}
+
+/// Parses an operand written with an explicit mode
+fn parse_arg(text: &str) -> Result<Arg, AsmErrorKind> {
+  let text = text.trim();
+  let (mode, inner) = if let Some(rest) = text.strip_prefix('#') {
+    (1, rest)
+  } else if text.starts_with("rb[") && text.ends_with(']') {
+    (2, &text[3..text.len() - 1])
+  } else if text.starts_with('[') && text.ends_with(']') {
+    (0, &text[1..text.len() - 1])
+  } else {
+    return Err(AsmErrorKind::InvalidOperand(text.to_owned()));
+  };
+  Ok(Arg {
+    mode,
+    expr: parse_expr(inner)?,
+  })
+}
+
+/// Parses the instruction or directive part of a line
+fn parse_statement(text: &str) -> Result<Statement, AsmErrorKind> {
+  let (mnemonic, rest) = match text.find(char::is_whitespace) {
+    Some(i) => (&text[..i], text[i..].trim()),
+    None => (text, ""),
+  };
+  let operands: Vec<&str> = if rest.is_empty() {
+    Vec::new()
+  } else {
+    rest.split(',').collect()
+  };
+  let count = |expected: usize| {
+    if operands.len() == expected {
+      Ok(())
+    } else {
+      Err(AsmErrorKind::OperandCount {
+        expected,
+        found: operands.len(),
+      })
+    }
+  };
+
+  match mnemonic {
+    "db" => Ok(Statement::Data(
+      operands
+        .iter()
+        .map(|op| parse_expr(op))
+        .collect::<Result<_, _>>()?,
+    )),
+    "call" => {
+      count(1)?;
+      match parse_arg(operands[0])? {
+        Arg { mode: 1, expr } => Ok(Statement::Call(expr)),
+        _ => Err(AsmErrorKind::InvalidOperand(operands[0].trim().to_owned())),
+      }
+    }
+    "ret" => {
+      count(0)?;
+      Ok(Statement::Ret)
+    }
+    _ => {
+      let code =
+        opcode(mnemonic).ok_or_else(|| AsmErrorKind::UnknownMnemonic(mnemonic.to_owned()))?;
+      let op = IntcodeOperation::new(i64::from(code)).expect("known opcode");
+      count(op.len - 1)?;
+      let args: Vec<Arg> = operands
+        .iter()
+        .map(|op| parse_arg(op))
+        .collect::<Result<_, _>>()?;
+      if args
+        .iter()
+        .enumerate()
+        .any(|(i, arg)| arg.mode == 1 && op.writes_param(i))
+      {
+        return Err(AsmErrorKind::ImmediateWrite);
+      }
+      Ok(Statement::Instruction(code, args))
+    }
+  }
+}
+
+/// Assembles mnemonic source into a memory image
+pub fn assemble(source: &str) -> Result<Vec<i64>, AsmError> {
+  // first pass: parse statements and assign label addresses
+  let mut labels: HashMap<String, usize> = HashMap::new();
+  let mut statements: Vec<(usize, Statement)> = Vec::new();
+  let mut address = 0;
+  for (index, line) in source.lines().enumerate() {
+    let line_no = index + 1;
+    let error = |kind| AsmError {
+      line: line_no,
+      kind,
+    };
+
+    let mut text = match line.find(';') {
+      Some(i) => &line[..i],
+      None => line,
+    }
+    .trim();
+
+    // a line may start with any number of labels
+    while let Some(i) = text.find(':') {
+      let name = text[..i].trim();
+      if !is_label(name) {
+        break;
+      }
+      if labels.insert(name.to_owned(), address).is_some() {
+        return Err(error(AsmErrorKind::DuplicateLabel(name.to_owned())));
+      }
+      text = text[i + 1..].trim();
+    }
+    if text.is_empty() {
+      continue;
+    }
+
+    let statement = parse_statement(text).map_err(error)?;
+    address += statement.len();
+    statements.push((line_no, statement));
+  }
+
+  // second pass: resolve labels and encode
+  let mut image = Vec::with_capacity(address);
+  for (line_no, statement) in statements {
+    let resolve = |expr: &Expr| match expr {
+      Expr::Number(value) => Ok(*value),
+      Expr::Label(name, offset) => {
+        let error = |kind| AsmError {
+          line: line_no,
+          kind,
+        };
+        let addr = labels
+          .get(name)
+          .ok_or_else(|| error(AsmErrorKind::UndefinedLabel(name.clone())))?;
+        i64::try_from(*addr)
+          .ok()
+          .and_then(|addr| addr.checked_add(*offset))
+          .ok_or_else(|| error(AsmErrorKind::LabelOverflow(name.clone())))
+      }
+    };
+
+    let start = image.len();
+    match statement {
+      Statement::Instruction(opcode, args) => {
+        let mut operands = Vec::with_capacity(args.len());
+        for arg in args.iter() {
+          operands.push(Operand::new(resolve(&arg.expr)?, arg.mode).expect("valid mode"));
+        }
+        let ins = Instruction {
+          address: start,
+          opcode,
+          operands,
+        };
+        image.extend(ins.encode());
+      }
+      Statement::Data(values) => {
+        for value in values.iter() {
+          image.push(resolve(value)?);
+        }
+      }
+      Statement::Call(target) => {
+        // push the address following the call and jump to the target
+        let ret = (start + CALL_LEN) as i64;
+        image.extend(&[109, 1]);
+        image.extend(&[21101, ret, 0, 0]);
+        image.extend(&[1105, 1, resolve(&target)?]);
+      }
+      Statement::Ret => {
+        // pop the return address and jump back to it
+        image.extend(&[109, -1]);
+        image.extend(&[2105, 1, 1]);
+      }
+    }
+  }
+  Ok(image)
+}
+
+/// Produces assembler source that reassembles to the given image
+///
+/// Jump targets and position-mode addresses that land on the start of an
+/// instruction or data line are replaced with labels named after the address.
+pub fn source_from_image(image: &[i64]) -> String {
+  let lines = disasm::disassemble(image);
+  let starts: HashSet<usize> = lines.iter().map(|line| line.address()).collect();
+
+  // collect addresses referred to by other instructions
+  let mut targets: HashSet<usize> = HashSet::new();
+  for line in lines.iter() {
+    if let Line::Instruction(ins) = line {
+      for (i, op) in ins.operands.iter().enumerate() {
+        let is_jump = (ins.opcode == 5 || ins.opcode == 6) && i == 1;
+        let refers = match op {
+          Operand::Position(_) => true,
+          Operand::Immediate(_) => is_jump,
+          Operand::Relative(_) => false,
+        };
+        if refers && op.value() >= 0 && starts.contains(&(op.value() as usize)) {
+          targets.insert(op.value() as usize);
+        }
+      }
+    }
+  }
+
+  let label = |value: i64| {
+    if value >= 0 && targets.contains(&(value as usize)) {
+      format!("L{}", value)
+    } else {
+      value.to_string()
+    }
+  };
+
+  let mut source = String::new();
+  for line in lines.iter() {
+    if targets.contains(&line.address()) {
+      source.push_str(&format!("L{}:\n", line.address()));
+    }
+    match line {
+      Line::Instruction(ins) => {
+        let operands: Vec<String> = ins
+          .operands
+          .iter()
+          .enumerate()
+          .map(|(i, op)| match op {
+            Operand::Position(v) => format!("[{}]", label(*v)),
+            Operand::Immediate(v) if (ins.opcode == 5 || ins.opcode == 6) && i == 1 => {
+              format!("#{}", label(*v))
+            }
+            _ => op.to_string(),
+          })
+          .collect();
+        if operands.is_empty() {
+          source.push_str(&format!("    {}\n", ins.mnemonic()));
+        } else {
+          source.push_str(&format!("    {} {}\n", ins.mnemonic(), operands.join(", ")));
+        }
+      }
+      Line::Data { values, .. } => {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        source.push_str(&format!("    db {}\n", values.join(", ")));
+      }
+    }
+  }
+  source
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::program::IntcodeProgram;
+  #[test]
+  fn assemble_instructions() {
+    let source = "
+      in [x]            ; read a value
+      mul [x], #2, rb[1]
+      out rb[1]
+      hlt
+    x: db 0
+    ";
+    let image = assemble(source).unwrap();
+    assert_eq!(image, vec![3, 9, 21002, 9, 2, 1, 204, 1, 99, 0]);
+
+    let image = assemble("in [x]\nout [x]\nhlt\nx: db 0").unwrap();
+    assert_eq!(image, vec![3, 5, 4, 5, 99, 0]);
+    let mut prg = IntcodeProgram::from_image(&image, vec![17]);
+    prg.run_to_halt().unwrap();
+    assert_eq!(prg.output, vec![17]);
+  }
+
+  #[test]
+  fn labels_and_data() {
+    let source = "
+    start: jf #0, #end
+           db 1, -2, start, end-1
+    end:   hlt
+    ";
+    assert_eq!(assemble(source).unwrap(), vec![1106, 0, 7, 1, -2, 0, 6, 99]);
+
+    // labels may share a line and an address
+    assert_eq!(assemble("a: b: jt #1, #b").unwrap(), vec![1105, 1, 0]);
+  }
+
+  #[test]
+  fn call_and_ret() {
+    // output 5 doubled twice using a subroutine
+    let source = "
+          arb #stack
+          add #5, #0, [x]
+          call #double
+          call #double
+          out [x]
+          hlt
+    double:
+          mul [x], #2, [x]
+          ret
+    x:    db 0
+    stack:
+    ";
+    let image = assemble(source).unwrap();
+    let mut prg = IntcodeProgram::from_image(&image, vec![]);
+    prg.run_to_halt().unwrap();
+    assert_eq!(prg.output, vec![20]);
+  }
+
+  #[test]
+  fn assemble_errors() {
+    let error = |source: &str| assemble(source).unwrap_err();
+    assert_eq!(
+      error("hlt\nnop"),
+      AsmError {
+        line: 2,
+        kind: AsmErrorKind::UnknownMnemonic("nop".to_owned())
+      }
+    );
+    assert_eq!(
+      error("add [1], [2]").kind,
+      AsmErrorKind::OperandCount {
+        expected: 3,
+        found: 2
+      }
+    );
+    assert_eq!(
+      error("out 5").kind,
+      AsmErrorKind::InvalidOperand("5".to_owned())
+    );
+    assert_eq!(error("in #5").kind, AsmErrorKind::ImmediateWrite);
+    assert_eq!(
+      error("jt #1, #nowhere").kind,
+      AsmErrorKind::UndefinedLabel("nowhere".to_owned())
+    );
+    assert_eq!(
+      error("a: hlt\na: hlt").kind,
+      AsmErrorKind::DuplicateLabel("a".to_owned())
+    );
+    assert_eq!(
+      error("db 0\nx: db 1\ndb x+9223372036854775807"),
+      AsmError {
+        line: 3,
+        kind: AsmErrorKind::LabelOverflow("x".to_owned())
+      }
+    );
+  }
+
+  #[test]
+  fn round_trip_image() {
+    // day 5 sample comparing the input to 8
+    let image = [
+      3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0,
+      1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20, 1105,
+      1, 46, 98, 99,
+    ];
+    let source = source_from_image(&image);
+    assert!(source.contains("L22:\n"));
+    assert!(source.contains("jt [20], #L22"));
+    assert_eq!(assemble(&source).unwrap(), image.to_vec());
+  }
+}
@@ -0,0 +1,43 @@
+//! Assembles Intcode mnemonic source into comma-separated Intcode, or with
+//! `-d` turns an Intcode program back into assembler source
+//!
+//! Usage: `asm [-d] <file>`
+extern crate intcode;
+
+use intcode::{asm, ProgramLoader};
+use std::env;
+use std::fs;
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  let (decode, path) = match args.len() {
+    2 => (false, &args[1]),
+    3 if args[1] == "-d" => (true, &args[2]),
+    _ => {
+      eprintln!("Usage: {} [-d] <file>", args[0]);
+      process::exit(2);
+    }
+  };
+
+  if decode {
+    let image = ProgramLoader::with_comments()
+      .load(path)
+      .unwrap_or_else(|err| {
+        eprintln!("Problem loading Intcode program from input: {}", err);
+        process::exit(1);
+      });
+    print!("{}", asm::source_from_image(&image));
+  } else {
+    let source = fs::read_to_string(path).unwrap_or_else(|err| {
+      eprintln!("Problem reading assembler source: {}", err);
+      process::exit(1);
+    });
+    let image = asm::assemble(&source).unwrap_or_else(|err| {
+      eprintln!("Problem assembling program: {}", err);
+      process::exit(1);
+    });
+    let values: Vec<String> = image.iter().map(|v| v.to_string()).collect();
+    println!("{}", values.join(","));
+  }
+}
@@ -0,0 +1,25 @@
+//! Prints the control-flow graph of an Intcode program in Graphviz DOT format
+//!
+//! Usage: `cfg <program file> | dot -Tsvg > program.svg`
+extern crate intcode;
+
+use intcode::cfg::ControlFlowGraph;
+use intcode::ProgramLoader;
+use std::env;
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 {
+    eprintln!("Usage: {} <program file>", args[0]);
+    process::exit(2);
+  }
+
+  let image = ProgramLoader::with_comments()
+    .load(&args[1])
+    .unwrap_or_else(|err| {
+      eprintln!("Problem loading Intcode program from input: {}", err);
+      process::exit(1);
+    });
+  print!("{}", ControlFlowGraph::recover(&image).to_dot());
+}
@@ -0,0 +1,24 @@
+//! Prints an annotated disassembly listing of an Intcode program
+//!
+//! Usage: `disasm <program file>`
+extern crate intcode;
+
+use intcode::{disasm, ProgramLoader};
+use std::env;
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 {
+    eprintln!("Usage: {} <program file>", args[0]);
+    process::exit(2);
+  }
+
+  let image = ProgramLoader::with_comments()
+    .load(&args[1])
+    .unwrap_or_else(|err| {
+      eprintln!("Problem loading Intcode program from input: {}", err);
+      process::exit(1);
+    });
+  print!("{}", disasm::listing(&image));
+}
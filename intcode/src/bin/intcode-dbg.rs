@@ -0,0 +1,43 @@
+//! Interactive debugger for Intcode programs
+//!
+//! Usage: `intcode-dbg <program file>`, then type `help` for a list of commands.
+extern crate intcode;
+
+use intcode::debugger::Debugger;
+use intcode::{IntcodeProgram, ProgramLoader};
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 {
+    eprintln!("Usage: {} <program file>", args[0]);
+    process::exit(2);
+  }
+
+  let image = ProgramLoader::with_comments()
+    .load(&args[1])
+    .unwrap_or_else(|err| {
+      eprintln!("Problem loading Intcode program from input: {}", err);
+      process::exit(1);
+    });
+  let mut dbg = Debugger::new(IntcodeProgram::from_image(&image, vec![]));
+
+  let stdin = io::stdin();
+  let stdout = io::stdout();
+  let mut out = stdout.lock();
+  loop {
+    write!(out, "(dbg) ").unwrap();
+    out.flush().unwrap();
+
+    let mut line = String::new();
+    match stdin.lock().read_line(&mut line) {
+      Ok(0) | Err(_) => break,
+      Ok(_) => (),
+    }
+    if !dbg.execute(&line, &mut out).unwrap() {
+      break;
+    }
+  }
+}
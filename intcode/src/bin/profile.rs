@@ -0,0 +1,43 @@
+//! Runs an Intcode program under the profiler and prints its output and a
+//! report of where execution time and memory traffic went
+//!
+//! Usage: `profile <program file> [input ...]`
+extern crate intcode;
+
+use intcode::{IntcodeProgram, Profiler, ProgramLoader};
+use std::env;
+use std::process;
+
+/// Number of addresses listed in each section of the report
+const REPORT_LIMIT: usize = 20;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() < 2 {
+    eprintln!("Usage: {} <program file> [input ...]", args[0]);
+    process::exit(2);
+  }
+
+  let image = ProgramLoader::with_comments()
+    .load(&args[1])
+    .unwrap_or_else(|err| {
+      eprintln!("Problem loading Intcode program from input: {}", err);
+      process::exit(1);
+    });
+  let input: Vec<i64> = args[2..]
+    .iter()
+    .map(|v| {
+      v.parse().unwrap_or_else(|_| {
+        eprintln!("Invalid input value {:?}", v);
+        process::exit(2);
+      })
+    })
+    .collect();
+
+  let mut prg = IntcodeProgram::from_image(&image, input);
+  let mut profiler = Profiler::new();
+  let status = prg.run_traced(&mut profiler);
+  println!("status: {:?}", status);
+  println!("output: {:?}\n", prg.output);
+  print!("{}", profiler.report(REPORT_LIMIT));
+}
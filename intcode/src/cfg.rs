@@ -0,0 +1,262 @@
+//! Static control-flow graph recovery
+//!
+//! Starting at address 0, instructions are decoded and followed through
+//! fallthrough and immediate-mode jump targets of `jt` (5) and `jf` (6).
+//! Jumps whose target is read from memory cannot be followed statically and
+//! are kept as unresolved edges. Self-modifying code is not accounted for.
+use crate::disasm::{self, Instruction, Operand};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::vec::Vec;
+
+/// Edge leaving a basic block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+  /// Jump to a known address
+  Jump(usize),
+  /// Execution continuing at the next address
+  Fallthrough(usize),
+  /// Jump to an address only known at run time, given by the operand
+  Unresolved(Operand),
+}
+
+/// Sequence of instructions with a single entry and a single exit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasicBlock {
+  /// Address of the first instruction
+  pub start: usize,
+  pub instructions: Vec<Instruction>,
+  /// Edges leaving the last instruction; empty if it halts
+  pub edges: Vec<Edge>,
+}
+
+/// Basic blocks reachable from address 0, keyed by start address
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ControlFlowGraph {
+  pub blocks: BTreeMap<usize, BasicBlock>,
+  /// Reachable addresses that do not hold a valid instruction
+  pub invalid: BTreeSet<usize>,
+}
+
+/// Returns whether the instruction is a conditional jump
+fn is_jump(ins: &Instruction) -> bool {
+  ins.opcode == 5 || ins.opcode == 6
+}
+
+/// Returns the edges leaving a single instruction
+fn successors(ins: &Instruction) -> Vec<Edge> {
+  let next = Edge::Fallthrough(ins.next_address());
+  match ins.opcode {
+    99 => Vec::new(),
+    5 | 6 => {
+      // a constant condition makes the jump always or never taken
+      let (taken, not_taken) = match ins.operands[0] {
+        Operand::Immediate(v) => {
+          let taken = (v != 0) == (ins.opcode == 5);
+          (taken, !taken)
+        }
+        _ => (true, true),
+      };
+
+      let mut edges = Vec::new();
+      if taken {
+        edges.push(match ins.operands[1] {
+          Operand::Immediate(t) if t >= 0 => Edge::Jump(t as usize),
+          op => Edge::Unresolved(op),
+        });
+      }
+      if not_taken {
+        edges.push(next);
+      }
+      edges
+    }
+    _ => vec![next],
+  }
+}
+
+impl ControlFlowGraph {
+  /// Recovers the control-flow graph of a memory image
+  pub fn recover(image: &[i64]) -> ControlFlowGraph {
+    // find every reachable instruction and where blocks must start
+    let mut instructions: BTreeMap<usize, Instruction> = BTreeMap::new();
+    let mut leaders: BTreeSet<usize> = BTreeSet::new();
+    let mut invalid: BTreeSet<usize> = BTreeSet::new();
+    let mut work = vec![0];
+    leaders.insert(0);
+    while let Some(addr) = work.pop() {
+      if instructions.contains_key(&addr) || invalid.contains(&addr) {
+        continue;
+      }
+      let ins = match disasm::decode_instruction(image, addr) {
+        Some(ins) => ins,
+        None => {
+          invalid.insert(addr);
+          continue;
+        }
+      };
+      for edge in successors(&ins) {
+        match edge {
+          Edge::Jump(target) => {
+            leaders.insert(target);
+            work.push(target);
+          }
+          Edge::Fallthrough(next) => {
+            if is_jump(&ins) {
+              leaders.insert(next);
+            }
+            work.push(next);
+          }
+          Edge::Unresolved(_) => (),
+        }
+      }
+      instructions.insert(addr, ins);
+    }
+
+    // split the instructions into blocks at leaders and after jumps
+    let mut blocks = BTreeMap::new();
+    for &start in leaders.iter().filter(|a| instructions.contains_key(a)) {
+      let mut block = BasicBlock {
+        start,
+        instructions: Vec::new(),
+        edges: Vec::new(),
+      };
+      let mut addr = start;
+      loop {
+        let ins = instructions[&addr].clone();
+        let next = ins.next_address();
+        let ends = is_jump(&ins)
+          || ins.opcode == 99
+          || !instructions.contains_key(&next)
+          || leaders.contains(&next);
+        block.instructions.push(ins);
+        if ends {
+          block.edges = successors(&block.instructions[block.instructions.len() - 1]);
+          break;
+        }
+        addr = next;
+      }
+      blocks.insert(start, block);
+    }
+
+    ControlFlowGraph { blocks, invalid }
+  }
+
+  /// Returns the block containing the instruction at the given address
+  pub fn block_at(&self, address: usize) -> Option<&BasicBlock> {
+    self
+      .blocks
+      .range(..=address)
+      .rev()
+      .map(|(_, block)| block)
+      .find(|block| block.instructions.iter().any(|ins| ins.address == address))
+  }
+
+  /// Renders the graph in Graphviz DOT format
+  ///
+  /// Jumps are solid edges, fallthroughs dashed, and unresolved jumps dotted
+  /// edges to a `?` node labeled with the target operand.
+  pub fn to_dot(&self) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph intcode {{").unwrap();
+    writeln!(out, "  node [shape=box, fontname=\"monospace\"];").unwrap();
+
+    for block in self.blocks.values() {
+      let label: String = block
+        .instructions
+        .iter()
+        .map(|ins| format!("{}: {}\\l", ins.address, ins))
+        .collect();
+      writeln!(out, "  b{} [label=\"{}\"];", block.start, label).unwrap();
+    }
+    for addr in self.invalid.iter() {
+      writeln!(out, "  b{} [label=\"{}: invalid\", color=red];", addr, addr).unwrap();
+    }
+
+    let mut unresolved = 0;
+    for block in self.blocks.values() {
+      for edge in block.edges.iter() {
+        match edge {
+          Edge::Jump(target) => writeln!(out, "  b{} -> b{};", block.start, target).unwrap(),
+          Edge::Fallthrough(next) => {
+            writeln!(out, "  b{} -> b{} [style=dashed];", block.start, next).unwrap()
+          }
+          Edge::Unresolved(op) => {
+            writeln!(out, "  u{} [label=\"?\", shape=circle];", unresolved).unwrap();
+            writeln!(
+              out,
+              "  b{} -> u{} [style=dotted, label=\"{}\"];",
+              block.start, unresolved, op
+            )
+            .unwrap();
+            unresolved += 1;
+          }
+        }
+      }
+    }
+    writeln!(out, "}}").unwrap();
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn recover_blocks() {
+    // 0: in [13]; 2: jf [13], #11; 5: out [13]; 7: jt #1, #0; 10: db 42; 11: hlt; 12: db 0, 0
+    let image = [3, 13, 1006, 13, 11, 4, 13, 1105, 1, 0, 42, 99, 0, 0];
+    let graph = ControlFlowGraph::recover(&image);
+
+    let starts: Vec<usize> = graph.blocks.keys().copied().collect();
+    assert_eq!(starts, vec![0, 5, 11]);
+    assert_eq!(graph.blocks[&0].instructions.len(), 2);
+    assert_eq!(
+      graph.blocks[&0].edges,
+      vec![Edge::Jump(11), Edge::Fallthrough(5)]
+    );
+
+    // the unconditional jump has no fallthrough, so 10 is never reached
+    assert_eq!(graph.blocks[&5].edges, vec![Edge::Jump(0)]);
+    assert!(graph.blocks[&11].edges.is_empty());
+    assert!(graph.invalid.is_empty());
+
+    assert_eq!(graph.block_at(7).unwrap().start, 5);
+    assert_eq!(graph.block_at(10), None);
+  }
+
+  #[test]
+  fn unresolved_and_invalid() {
+    // 0: jt [7], rb[2]; 3: add #1, #1, [7]; 7: db 55
+    let image = [2005, 7, 2, 1101, 1, 1, 7, 55];
+    let graph = ControlFlowGraph::recover(&image);
+    assert_eq!(
+      graph.blocks[&0].edges,
+      vec![Edge::Unresolved(Operand::Relative(2)), Edge::Fallthrough(3)]
+    );
+    assert_eq!(graph.blocks[&3].edges, vec![Edge::Fallthrough(7)]);
+    assert_eq!(graph.invalid.iter().copied().collect::<Vec<_>>(), vec![7]);
+  }
+
+  #[test]
+  fn split_at_jump_target() {
+    // 0: add ...; 4: add ...; 8: jt #1, #4
+    let image = [1101, 0, 0, 12, 1101, 0, 0, 12, 1105, 1, 4, 99, 0];
+    let graph = ControlFlowGraph::recover(&image);
+    let starts: Vec<usize> = graph.blocks.keys().copied().collect();
+    assert_eq!(starts, vec![0, 4]);
+    assert_eq!(graph.blocks[&0].edges, vec![Edge::Fallthrough(4)]);
+    assert_eq!(graph.blocks[&4].instructions.len(), 2);
+  }
+
+  #[test]
+  fn dot_export() {
+    let image = [2005, 7, 2, 1101, 1, 1, 7, 55];
+    let dot = ControlFlowGraph::recover(&image).to_dot();
+    assert!(dot.starts_with("digraph intcode {\n"));
+    assert!(dot.contains("  b0 [label=\"0: jt [7], rb[2]\\l\"];\n"));
+    assert!(dot.contains("  b0 -> u0 [style=dotted, label=\"rb[2]\"];\n"));
+    assert!(dot.contains("  b0 -> b3 [style=dashed];\n"));
+    assert!(dot.contains("  b7 [label=\"7: invalid\", color=red];\n"));
+    assert!(dot.ends_with("}\n"));
+  }
+}
@@ -0,0 +1,399 @@
+//! Command interpreter behind the `intcode-dbg` binary
+//!
+//! Commands (short forms in parentheses):
+//!
+//! ```text
+//! step [N]             (s)  execute N instructions, 1 by default
+//! continue             (c)  run until a breakpoint, watchpoint, input wait, or halt
+//! break [ADDR]         (b)  set a breakpoint, or list breakpoints
+//! delete ADDR          (d)  remove a breakpoint
+//! watch [ADDR]         (w)  stop when the cell at ADDR changes, or list watchpoints
+//! unwatch ADDR         (u)  remove a watchpoint
+//! regs                 (r)  show the registers and current instruction
+//! mem START [COUNT]    (x)  dump COUNT memory cells, 16 by default, 4096 at most
+//! input V [V ...]      (i)  queue input values
+//! output               (o)  show and clear pending output
+//! help                 (h)  list commands
+//! quit                 (q)  leave the debugger
+//! ```
+use crate::disasm::{self, Line};
+use crate::error::IntcodeError;
+use crate::program::{IntcodeProgram, RunStatus};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+use std::vec::Vec;
+
+/// Number of memory cells shown per line of a memory dump
+const DUMP_WIDTH: usize = 8;
+
+/// Most memory cells a single dump shows
+const DUMP_LIMIT: usize = 4096;
+
+/// Reason execution stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stop {
+  /// Requested number of instructions was executed
+  Steps,
+  /// Instruction pointer reached a breakpoint
+  Breakpoint(usize),
+  /// Watched cell changed from one value to another
+  Watchpoint(usize, i64, i64),
+  /// Program is waiting for input
+  NeedsInput,
+  /// Program reached the exit instruction
+  Halted,
+  /// Program's instruction budget or deadline ran out
+  BudgetExhausted,
+  /// Instruction failed
+  Fault(IntcodeError),
+}
+
+/// Interactive debugger wrapping an Intcode program
+#[derive(Debug)]
+pub struct Debugger {
+  pub program: IntcodeProgram,
+  breakpoints: BTreeSet<usize>,
+  /// Watched addresses and their last seen values
+  watchpoints: BTreeMap<usize, i64>,
+}
+
+impl Debugger {
+  /// Creates a debugger for the given program
+  pub fn new(program: IntcodeProgram) -> Debugger {
+    Debugger {
+      program,
+      breakpoints: BTreeSet::new(),
+      watchpoints: BTreeMap::new(),
+    }
+  }
+
+  /// Runs a single command, writing its results to `out`
+  ///
+  /// Returns `false` once the user asks to quit.
+  pub fn execute<W: Write>(&mut self, command: &str, out: &mut W) -> io::Result<bool> {
+    let mut words = command.split_whitespace();
+    let name = match words.next() {
+      Some(name) => name,
+      None => return Ok(true),
+    };
+    let args: Vec<&str> = words.collect();
+
+    match name {
+      "s" | "step" => match parse_arg(&args, 0, 1) {
+        Some(count) => self.advance(Some(count), out)?,
+        None => writeln!(out, "usage: step [N]")?,
+      },
+      "c" | "continue" => self.advance(None, out)?,
+      "b" | "break" => {
+        if args.is_empty() {
+          list(out, "breakpoints", self.breakpoints.iter().copied())?;
+        } else if let Some(addr) = parse_required(&args, 0) {
+          self.breakpoints.insert(addr);
+          writeln!(out, "breakpoint at {}", addr)?;
+        } else {
+          writeln!(out, "usage: break [ADDR]")?;
+        }
+      }
+      "d" | "delete" => match parse_required(&args, 0) {
+        Some(addr) if self.breakpoints.remove(&addr) => {
+          writeln!(out, "deleted breakpoint at {}", addr)?
+        }
+        Some(addr) => writeln!(out, "no breakpoint at {}", addr)?,
+        None => writeln!(out, "usage: delete ADDR")?,
+      },
+      "w" | "watch" => {
+        if args.is_empty() {
+          list(out, "watchpoints", self.watchpoints.keys().copied())?;
+        } else if let Some(addr) = parse_required(&args, 0) {
+          let value = self.program.get_value(addr);
+          self.watchpoints.insert(addr, value);
+          writeln!(out, "watching {} (currently {})", addr, value)?;
+        } else {
+          writeln!(out, "usage: watch [ADDR]")?;
+        }
+      }
+      "u" | "unwatch" => match parse_required(&args, 0) {
+        Some(addr) if self.watchpoints.remove(&addr).is_some() => {
+          writeln!(out, "stopped watching {}", addr)?
+        }
+        Some(addr) => writeln!(out, "not watching {}", addr)?,
+        None => writeln!(out, "usage: unwatch ADDR")?,
+      },
+      "r" | "regs" => self.show_registers(out)?,
+      "x" | "mem" => match (parse_required(&args, 0), parse_arg(&args, 1, 16)) {
+        (Some(start), Some(count)) => self.dump(start, count, out)?,
+        _ => writeln!(out, "usage: mem START [COUNT]")?,
+      },
+      "i" | "input" => {
+        let values: Option<Vec<i64>> = args.iter().map(|v| v.parse().ok()).collect();
+        match values {
+          Some(ref values) if !values.is_empty() => {
+            for value in values.iter() {
+              self.program.feed(*value);
+            }
+            writeln!(out, "queued {} input value(s)", values.len())?;
+          }
+          _ => writeln!(out, "usage: input V [V ...]")?,
+        }
+      }
+      "o" | "output" => {
+        let output = self.program.take_output();
+        if output.is_empty() {
+          writeln!(out, "no pending output")?;
+        } else {
+          list(out, "output", output.into_iter())?;
+        }
+      }
+      "h" | "help" => write!(out, "{}", HELP)?,
+      "q" | "quit" => return Ok(false),
+      _ => writeln!(out, "unknown command {:?}; type help for a list", name)?,
+    }
+    Ok(true)
+  }
+
+  /// Executes instructions until the limit is reached or something stops the program
+  fn advance<W: Write>(&mut self, limit: Option<usize>, out: &mut W) -> io::Result<()> {
+    let mut executed = 0;
+    let stop = loop {
+      if limit == Some(executed) {
+        break Stop::Steps;
+      }
+      let halted = self.program.is_halted();
+      match self.program.step() {
+        Err(err) => break Stop::Fault(err),
+        Ok(RunStatus::NeedsInput) => break Stop::NeedsInput,
+        Ok(RunStatus::BudgetExhausted) => break Stop::BudgetExhausted,
+        Ok(RunStatus::Halted) => {
+          // count the exit instruction itself, but not steps on a halted machine
+          if !halted {
+            executed += 1;
+          }
+          break Stop::Halted;
+        }
+        Ok(RunStatus::Running) | Ok(RunStatus::Output(_)) => executed += 1,
+      }
+
+      // stop on the first watched cell that changed
+      let program = &self.program;
+      let changed = self
+        .watchpoints
+        .iter_mut()
+        .map(|(addr, last)| (*addr, last, program.get_value(*addr)))
+        .find(|(_, last, value)| **last != *value)
+        .map(|(addr, last, value)| {
+          let old = *last;
+          *last = value;
+          Stop::Watchpoint(addr, old, value)
+        });
+      if let Some(stop) = changed {
+        break stop;
+      }
+
+      let ip = self.program.instruction_pointer();
+      if self.breakpoints.contains(&ip) {
+        break Stop::Breakpoint(ip);
+      }
+    };
+
+    writeln!(out, "executed {} instruction(s)", executed)?;
+    match stop {
+      Stop::Steps => (),
+      Stop::Breakpoint(addr) => writeln!(out, "stopped at breakpoint {}", addr)?,
+      Stop::Watchpoint(addr, old, new) => {
+        writeln!(out, "watchpoint {} changed from {} to {}", addr, old, new)?
+      }
+      Stop::NeedsInput => writeln!(out, "waiting for input")?,
+      Stop::Halted => writeln!(out, "program halted")?,
+      Stop::BudgetExhausted => writeln!(out, "instruction budget exhausted")?,
+      Stop::Fault(err) => writeln!(out, "error: {}", err)?,
+    }
+    if !self.program.output.is_empty() {
+      writeln!(
+        out,
+        "{} value(s) of pending output",
+        self.program.output.len()
+      )?;
+    }
+    self.show_current(out)
+  }
+
+  /// Prints the instruction at the instruction pointer
+  fn show_current<W: Write>(&self, out: &mut W) -> io::Result<()> {
+    let ip = self.program.instruction_pointer();
+    let window: Vec<i64> = (ip..ip + 4).map(|a| self.program.get_value(a)).collect();
+    let line = match disasm::decode_instruction(&window, 0) {
+      Some(mut ins) => {
+        ins.address = ip;
+        Line::Instruction(ins)
+      }
+      None => Line::Data {
+        address: ip,
+        values: vec![window[0]],
+      },
+    };
+    writeln!(out, "{}", line)
+  }
+
+  /// Prints the registers and the current instruction
+  fn show_registers<W: Write>(&self, out: &mut W) -> io::Result<()> {
+    writeln!(
+      out,
+      "ip {}  rb {}  halted {}  input {:?}",
+      self.program.instruction_pointer(),
+      self.program.relative_base(),
+      self.program.is_halted(),
+      self.program.input
+    )?;
+    self.show_current(out)
+  }
+
+  /// Prints a range of memory cells, stopping at the end of the address space
+  fn dump<W: Write>(&self, start: usize, count: usize, out: &mut W) -> io::Result<()> {
+    let end = start.saturating_add(count.min(DUMP_LIMIT));
+    let mut addr = start;
+    while addr < end {
+      let row_end = end.min(addr.saturating_add(DUMP_WIDTH));
+      let values: Vec<String> = (addr..row_end)
+        .map(|a| format!("{:>8}", self.program.get_value(a)))
+        .collect();
+      writeln!(out, "{:>6}: {}", addr, values.join(" "))?;
+      addr = row_end;
+    }
+    Ok(())
+  }
+}
+
+/// Summary of the available commands
+const HELP: &str = "\
+step [N]           execute N instructions (default 1)
+continue           run until a breakpoint, watchpoint, input wait, or halt
+break [ADDR]       set a breakpoint, or list breakpoints
+delete ADDR        remove a breakpoint
+watch [ADDR]       stop when the cell at ADDR changes, or list watchpoints
+unwatch ADDR       remove a watchpoint
+regs               show the registers and current instruction
+mem START [COUNT]  dump COUNT memory cells (default 16, at most 4096)
+input V [V ...]    queue input values
+output             show and clear pending output
+quit               leave the debugger
+";
+
+/// Parses the argument at the given index, using the default if it is missing
+fn parse_arg(args: &[&str], index: usize, default: usize) -> Option<usize> {
+  match args.get(index) {
+    Some(arg) => arg.parse().ok(),
+    None => Some(default),
+  }
+}
+
+/// Parses the argument at the given index, which must be present
+fn parse_required(args: &[&str], index: usize) -> Option<usize> {
+  args.get(index)?.parse().ok()
+}
+
+/// Prints a labeled list of values on one line
+fn list<W: Write, T: ToString, It: Iterator<Item = T>>(
+  out: &mut W,
+  label: &str,
+  values: It,
+) -> io::Result<()> {
+  let values: Vec<String> = values.map(|v| v.to_string()).collect();
+  if values.is_empty() {
+    writeln!(out, "no {}", label)
+  } else {
+    writeln!(out, "{}: {}", label, values.join(", "))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Runs the commands in order and returns everything written by the last one
+  fn run(dbg: &mut Debugger, commands: &[&str]) -> String {
+    let mut out: Vec<u8> = Vec::new();
+    for command in commands.iter() {
+      out.clear();
+      assert!(dbg.execute(command, &mut out).unwrap());
+    }
+    String::from_utf8(out).unwrap()
+  }
+
+  /// Creates a debugger for a program that doubles each input until it reads 0
+  fn doubler() -> Debugger {
+    let source = "3,15,1006,15,14,1002,15,2,16,4,16,1105,1,0,99,0,0";
+    Debugger::new(IntcodeProgram::new(source, vec![]).unwrap())
+  }
+
+  #[test]
+  fn step_and_registers() {
+    let mut dbg = Debugger::new(IntcodeProgram::new("109,5,1101,1,2,0,99", vec![]).unwrap());
+    let out = run(&mut dbg, &["step"]);
+    assert!(out.contains("executed 1 instruction(s)"));
+    assert!(out.contains("add #1, #2, [0]"));
+
+    let out = run(&mut dbg, &["regs"]);
+    assert!(out.starts_with("ip 2  rb 5  halted false"));
+
+    let out = run(&mut dbg, &["s 5"]);
+    assert!(out.contains("executed 2 instruction(s)"));
+    assert!(out.contains("program halted"));
+    assert_eq!(dbg.program.get_value(0), 3);
+  }
+
+  #[test]
+  fn breakpoints_and_input() {
+    let mut dbg = Debugger::new(IntcodeProgram::new("3,9,4,9,3,9,4,9,99,0", vec![]).unwrap());
+    let out = run(&mut dbg, &["continue"]);
+    assert!(out.contains("waiting for input"));
+
+    let out = run(&mut dbg, &["break 4", "input 7 8", "c"]);
+    assert!(out.contains("executed 2 instruction(s)"));
+    assert!(out.contains("stopped at breakpoint 4"));
+    assert!(out.contains("1 value(s) of pending output"));
+
+    let out = run(&mut dbg, &["output"]);
+    assert_eq!(out, "output: 7\n");
+    let out = run(&mut dbg, &["delete 4", "break", "c", "o"]);
+    assert_eq!(out, "output: 8\n");
+    assert!(dbg.program.is_halted());
+  }
+
+  #[test]
+  fn watchpoints() {
+    let mut dbg = doubler();
+    let out = run(&mut dbg, &["watch 16", "input 3 0", "c"]);
+    assert!(out.contains("watchpoint 16 changed from 0 to 6"));
+    assert_eq!(dbg.program.instruction_pointer(), 9);
+
+    let out = run(&mut dbg, &["unwatch 16", "c"]);
+    assert!(out.contains("program halted"));
+  }
+
+  #[test]
+  fn memory_dump() {
+    let mut dbg = Debugger::new(IntcodeProgram::new("1,2,3,4,5,6,7,8,9,10", vec![]).unwrap());
+    let out = run(&mut dbg, &["mem 1 9"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("     1:        2"));
+    assert!(lines[1].starts_with("     9:       10"));
+
+    let out = run(&mut dbg, &["mem 18446744073709551613 5"]);
+    assert_eq!(out.lines().count(), 1);
+    assert!(out.starts_with("18446744073709551613:        0        0\n"));
+
+    let out = run(&mut dbg, &["mem 0 18446744073709551615"]);
+    assert_eq!(out.lines().count(), DUMP_LIMIT / DUMP_WIDTH);
+  }
+
+  #[test]
+  fn invalid_commands() {
+    let mut dbg = doubler();
+    assert!(run(&mut dbg, &["frobnicate"]).starts_with("unknown command"));
+    assert_eq!(run(&mut dbg, &["step x"]), "usage: step [N]\n");
+    assert_eq!(run(&mut dbg, &["input"]), "usage: input V [V ...]\n");
+    assert_eq!(run(&mut dbg, &[""]), "");
+    assert!(!dbg.execute("quit", &mut Vec::<u8>::new()).unwrap());
+  }
+}
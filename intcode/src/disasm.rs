@@ -0,0 +1,305 @@
+use crate::operation::IntcodeOperation;
+use std::fmt;
+use std::vec::Vec;
+
+/// Largest number of values shown on a single data line
+const DATA_LINE_WIDTH: usize = 8;
+
+/// Returns the mnemonic of the given opcode, if it is part of the instruction set
+pub fn mnemonic(opcode: u8) -> Option<&'static str> {
+  match opcode {
+    1 => Some("add"),
+    2 => Some("mul"),
+    3 => Some("in"),
+    4 => Some("out"),
+    5 => Some("jt"),
+    6 => Some("jf"),
+    7 => Some("lt"),
+    8 => Some("eq"),
+    9 => Some("arb"),
+    99 => Some("hlt"),
+    _ => None,
+  }
+}
+
+/// Instruction parameter together with its mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+  /// Value at the given address, shown as `[12]`
+  Position(i64),
+  /// The given value itself, shown as `#5`
+  Immediate(i64),
+  /// Value at the given offset from the relative base, shown as `rb[-1]`
+  Relative(i64),
+}
+
+impl Operand {
+  /// Creates an operand from a raw parameter value and its mode
+  pub fn new(value: i64, mode: u8) -> Option<Operand> {
+    match mode {
+      0 => Some(Operand::Position(value)),
+      1 => Some(Operand::Immediate(value)),
+      2 => Some(Operand::Relative(value)),
+      _ => None,
+    }
+  }
+
+  /// Returns the parameter mode of the operand
+  pub fn mode(&self) -> u8 {
+    match self {
+      Operand::Position(_) => 0,
+      Operand::Immediate(_) => 1,
+      Operand::Relative(_) => 2,
+    }
+  }
+
+  /// Returns the raw parameter value of the operand
+  pub fn value(&self) -> i64 {
+    match self {
+      Operand::Position(v) | Operand::Immediate(v) | Operand::Relative(v) => *v,
+    }
+  }
+}
+
+impl fmt::Display for Operand {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Operand::Position(v) => write!(f, "[{}]", v),
+      Operand::Immediate(v) => write!(f, "#{}", v),
+      Operand::Relative(v) => write!(f, "rb[{}]", v),
+    }
+  }
+}
+
+/// Decoded instruction at a known address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+  pub address: usize,
+  pub opcode: u8,
+  pub operands: Vec<Operand>,
+}
+
+impl Instruction {
+  /// Returns the mnemonic of the instruction
+  pub fn mnemonic(&self) -> &'static str {
+    mnemonic(self.opcode).unwrap_or("???")
+  }
+
+  /// Encodes the instruction back into memory values
+  pub fn encode(&self) -> Vec<i64> {
+    let modes: i64 = self
+      .operands
+      .iter()
+      .rev()
+      .fold(0, |acc, op| acc * 10 + i64::from(op.mode()));
+    let mut values = vec![modes * 100 + i64::from(self.opcode)];
+    values.extend(self.operands.iter().map(|op| op.value()));
+    values
+  }
+
+  /// Returns the address directly after the instruction
+  pub fn next_address(&self) -> usize {
+    self.address + self.operands.len() + 1
+  }
+}
+
+impl fmt::Display for Instruction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.mnemonic())?;
+    for (i, op) in self.operands.iter().enumerate() {
+      write!(f, "{}{}", if i == 0 { " " } else { ", " }, op)?;
+    }
+    Ok(())
+  }
+}
+
+/// Single line of a disassembly listing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Line {
+  /// Valid instruction
+  Instruction(Instruction),
+  /// Values starting at the given address that do not decode as instructions
+  Data { address: usize, values: Vec<i64> },
+}
+
+impl Line {
+  /// Returns the address of the first value on the line
+  pub fn address(&self) -> usize {
+    match self {
+      Line::Instruction(ins) => ins.address,
+      Line::Data { address, .. } => *address,
+    }
+  }
+}
+
+impl fmt::Display for Line {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Line::Instruction(ins) => {
+        let raw: Vec<String> = ins.encode().iter().map(|v| v.to_string()).collect();
+        write!(
+          f,
+          "{:>6}: {:<36} ; {}",
+          ins.address,
+          ins.to_string(),
+          raw.join(",")
+        )
+      }
+      Line::Data { address, values } => {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        write!(f, "{:>6}: db {}", address, values.join(", "))
+      }
+    }
+  }
+}
+
+/// Decodes the instruction at the given address of a memory image
+///
+/// Returns `None` if the value there is not a valid instruction: an unknown
+/// opcode, an unknown or unused parameter mode, an immediate-mode write
+/// target, or parameters running past the end of the image.
+pub fn decode_instruction(image: &[i64], address: usize) -> Option<Instruction> {
+  let raw = *image.get(address)?;
+  let op = IntcodeOperation::new(raw).ok()?;
+  let params = op.len - 1;
+
+  // mode digits beyond the instruction's parameters must be unset
+  if raw / 10_i64.pow(params as u32 + 2) != 0 {
+    return None;
+  }
+
+  let mut operands = Vec::with_capacity(params);
+  for index in 0..params {
+    let mode = op.modes[index];
+    if mode == 1 && op.writes_param(index) {
+      return None;
+    }
+    operands.push(Operand::new(*image.get(address + index + 1)?, mode)?);
+  }
+
+  Some(Instruction {
+    address,
+    opcode: op.opcode,
+    operands,
+  })
+}
+
+/// Disassembles a memory image from address 0 into listing lines
+///
+/// The image is swept linearly; values that do not decode are grouped into
+/// data lines of at most eight values.
+pub fn disassemble(image: &[i64]) -> Vec<Line> {
+  let mut lines = Vec::new();
+  let mut data: Vec<i64> = Vec::new();
+  let mut address = 0;
+
+  // emits pending data values as lines ending just before `end`
+  let flush = |lines: &mut Vec<Line>, data: &mut Vec<i64>, end: usize| {
+    let start = end - data.len();
+    for (i, chunk) in data.chunks(DATA_LINE_WIDTH).enumerate() {
+      lines.push(Line::Data {
+        address: start + i * DATA_LINE_WIDTH,
+        values: chunk.to_vec(),
+      });
+    }
+    data.clear();
+  };
+
+  while address < image.len() {
+    match decode_instruction(image, address) {
+      Some(ins) => {
+        flush(&mut lines, &mut data, address);
+        address = ins.next_address();
+        lines.push(Line::Instruction(ins));
+      }
+      None => {
+        data.push(image[address]);
+        address += 1;
+      }
+    }
+  }
+  flush(&mut lines, &mut data, address);
+  lines
+}
+
+/// Renders a full disassembly listing of a memory image
+pub fn listing(image: &[i64]) -> String {
+  disassemble(image)
+    .iter()
+    .map(|line| format!("{}\n", line))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn decode_operands() {
+    let image = [21101, 5, -1, 7, 204, -3, 99];
+    let ins = decode_instruction(&image, 0).unwrap();
+    assert_eq!(
+      ins.operands,
+      vec![
+        Operand::Immediate(5),
+        Operand::Immediate(-1),
+        Operand::Relative(7)
+      ]
+    );
+    assert_eq!(ins.to_string(), "add #5, #-1, rb[7]");
+    assert_eq!(ins.encode(), vec![21101, 5, -1, 7]);
+    assert_eq!(ins.next_address(), 4);
+
+    let ins = decode_instruction(&image, 4).unwrap();
+    assert_eq!(ins.to_string(), "out rb[-3]");
+    assert_eq!(decode_instruction(&image, 6).unwrap().to_string(), "hlt");
+  }
+
+  #[test]
+  fn reject_invalid_instructions() {
+    // unknown opcode
+    assert_eq!(decode_instruction(&[42], 0), None);
+    // unknown mode
+    assert_eq!(decode_instruction(&[304, 0], 0), None);
+    // mode set for a parameter the instruction does not have
+    assert_eq!(decode_instruction(&[1104, 0], 0), None);
+    // immediate-mode write target
+    assert_eq!(decode_instruction(&[10001, 0, 0, 0], 0), None);
+    assert_eq!(decode_instruction(&[103, 0], 0), None);
+    // truncated parameters
+    assert_eq!(decode_instruction(&[1, 0, 0], 0), None);
+    // negative value
+    assert_eq!(decode_instruction(&[-1], 0), None);
+  }
+
+  #[test]
+  fn disassemble_with_data() {
+    let image = [3, 9, 1005, 9, 8, 104, 1, 99, 99, -5, 50, 2];
+    let lines = disassemble(&image);
+    let addresses: Vec<usize> = lines.iter().map(|l| l.address()).collect();
+    assert_eq!(addresses, vec![0, 2, 5, 7, 8, 9]);
+    assert_eq!(
+      lines[5],
+      Line::Data {
+        address: 9,
+        values: vec![-5, 50, 2]
+      }
+    );
+
+    let text = listing(&image);
+    let text: Vec<&str> = text.lines().collect();
+    assert_eq!(text[0], format!("{:>6}: {:<36} ; 3,9", 0, "in [9]"));
+    assert_eq!(
+      text[1],
+      format!("{:>6}: {:<36} ; 1005,9,8", 2, "jt [9], #8")
+    );
+    assert_eq!(text[5], "     9: db -5, 50, 2");
+  }
+
+  #[test]
+  fn split_long_data() {
+    let image: Vec<i64> = (-10..0).collect();
+    let lines = disassemble(&image);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].address(), 8);
+  }
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::fmt;
+
+/// Cause of a failed Intcode instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntcodeErrorKind {
+  /// Opcode is not part of the Intcode instruction set
+  InvalidOpcode,
+  /// Parameter mode is not position (0), immediate (1), or relative (2)
+  InvalidMode,
+  /// Parameter that is written to uses immediate mode
+  ImmediateWrite,
+  /// Parameter or jump target resolves to the given address below zero
+  NegativeAddress(i64),
+  /// Relative-mode address does not fit in a 64-bit integer
+  AddressOverflow,
+  /// Relative base adjustment would move the base to the given value below zero
+  RelativeBaseUnderflow(i64),
+  /// Result of an add or multiply instruction does not fit in a 64-bit integer
+  ArithmeticOverflow,
+  /// Input instruction was reached with no input left to read
+  InputExhausted,
+  /// Instruction budget or deadline ran out before the program halted
+  BudgetExhausted,
+}
+
+/// Error raised while executing an Intcode program
+///
+/// Carries the machine state at the faulting instruction so callers can
+/// report or recover from the failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntcodeError {
+  /// Cause of the failure
+  pub kind: IntcodeErrorKind,
+  /// Address of the faulting instruction
+  pub instruction_pointer: usize,
+  /// Raw value of the faulting instruction (opcode and parameter modes)
+  pub opcode: i64,
+  /// Index of the offending parameter (0-based), if the failure concerns one
+  pub parameter: Option<usize>,
+  /// Mode of the offending parameter, if the failure concerns one
+  pub mode: Option<u8>,
+  /// Relative base at the time of the failure
+  pub relative_base: usize,
+}
+
+impl fmt::Display for IntcodeErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      IntcodeErrorKind::InvalidOpcode => write!(f, "invalid opcode"),
+      IntcodeErrorKind::InvalidMode => write!(f, "invalid parameter mode"),
+      IntcodeErrorKind::ImmediateWrite => write!(f, "write parameter in immediate mode"),
+      IntcodeErrorKind::NegativeAddress(addr) => write!(f, "negative address {}", addr),
+      IntcodeErrorKind::AddressOverflow => write!(f, "address overflow"),
+      IntcodeErrorKind::RelativeBaseUnderflow(base) => {
+        write!(f, "relative base underflow to {}", base)
+      }
+      IntcodeErrorKind::ArithmeticOverflow => write!(f, "arithmetic overflow"),
+      IntcodeErrorKind::InputExhausted => write!(f, "input exhausted"),
+      IntcodeErrorKind::BudgetExhausted => write!(f, "instruction budget exhausted"),
+    }
+  }
+}
+
+impl fmt::Display for IntcodeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} at address {} (instruction {}",
+      self.kind, self.instruction_pointer, self.opcode
+    )?;
+    if let Some(parameter) = self.parameter {
+      write!(f, ", parameter {}", parameter + 1)?;
+    }
+    if let Some(mode) = self.mode {
+      write!(f, ", mode {}", mode)?;
+    }
+    write!(f, ", relative base {})", self.relative_base)
+  }
+}
+
+impl Error for IntcodeError {}
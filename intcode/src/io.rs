@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::sync::mpsc::{Receiver, Sender};
+use std::vec::Vec;
+
+/// Source of values for the Intcode input instruction (opcode 3)
+pub trait InputSource {
+  /// Returns the next input value, or `None` if no input is available
+  fn read(&mut self) -> Option<i64>;
+}
+
+/// Destination of values emitted by the Intcode output instruction (opcode 4)
+pub trait OutputSink {
+  /// Receives a value emitted by the program
+  fn write(&mut self, value: i64);
+}
+
+/// Queue of provided input values, consumed from the front
+impl InputSource for VecDeque<i64> {
+  fn read(&mut self) -> Option<i64> {
+    self.pop_front()
+  }
+}
+
+/// Collects emitted values in order
+impl OutputSink for Vec<i64> {
+  fn write(&mut self, value: i64) {
+    self.push(value);
+  }
+}
+
+/// Blocks until a value is sent; returns `None` once all senders are dropped
+impl InputSource for Receiver<i64> {
+  fn read(&mut self) -> Option<i64> {
+    self.recv().ok()
+  }
+}
+
+/// Sends emitted values down the channel, dropping them if the receiver is gone
+impl OutputSink for Sender<i64> {
+  fn write(&mut self, value: i64) {
+    let _ = self.send(value);
+  }
+}
+
+/// Input source backed by a closure
+pub struct InputFn<F: FnMut() -> Option<i64>>(pub F);
+
+impl<F: FnMut() -> Option<i64>> InputSource for InputFn<F> {
+  fn read(&mut self) -> Option<i64> {
+    (self.0)()
+  }
+}
+
+impl<F: FnMut() -> Option<i64>> fmt::Debug for InputFn<F> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "InputFn")
+  }
+}
+
+/// Output sink backed by a closure
+pub struct OutputFn<F: FnMut(i64)>(pub F);
+
+impl<F: FnMut(i64)> OutputSink for OutputFn<F> {
+  fn write(&mut self, value: i64) {
+    (self.0)(value)
+  }
+}
+
+impl<F: FnMut(i64)> fmt::Debug for OutputFn<F> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "OutputFn")
+  }
+}
+
+/// Prompts the user for input on the console
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdinInput;
+
+impl InputSource for StdinInput {
+  /// Asks again until a valid integer is entered; returns `None` once
+  /// standard input is closed
+  fn read(&mut self) -> Option<i64> {
+    let stdin = io::stdin();
+    loop {
+      println!("Enter an integer:");
+      let mut input = String::new();
+      match stdin.lock().read_line(&mut input) {
+        Ok(0) | Err(_) => return None,
+        Ok(_) => {
+          if let Ok(value) = input.trim().parse::<i64>() {
+            return Some(value);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Prints emitted values to the console
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutOutput;
+
+impl OutputSink for StdoutOutput {
+  fn write(&mut self, value: i64) {
+    println!("Program emitted value: {}", value);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::mpsc;
+  #[test]
+  fn queue_and_vector() {
+    let mut input: VecDeque<i64> = vec![1, 2].into_iter().collect();
+    assert_eq!(input.read(), Some(1));
+    assert_eq!(input.read(), Some(2));
+    assert_eq!(input.read(), None);
+
+    let mut output: Vec<i64> = Vec::new();
+    output.write(3);
+    output.write(4);
+    assert_eq!(output, vec![3, 4]);
+  }
+
+  #[test]
+  fn closures() {
+    let mut next = 0;
+    let mut input = InputFn(|| {
+      next += 1;
+      if next <= 2 {
+        Some(next)
+      } else {
+        None
+      }
+    });
+    assert_eq!(input.read(), Some(1));
+    assert_eq!(input.read(), Some(2));
+    assert_eq!(input.read(), None);
+
+    let mut sum = 0;
+    {
+      let mut output = OutputFn(|value| sum += value);
+      output.write(5);
+      output.write(6);
+    }
+    assert_eq!(sum, 11);
+  }
+
+  #[test]
+  fn channels() {
+    let (mut tx, mut rx) = mpsc::channel();
+    tx.write(7);
+    assert_eq!(rx.read(), Some(7));
+    drop(tx);
+    assert_eq!(rx.read(), None);
+  }
+}
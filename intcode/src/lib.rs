@@ -0,0 +1,31 @@
+mod ascii;
+pub mod asm;
+pub mod cfg;
+pub mod debugger;
+pub mod disasm;
+mod error;
+mod io;
+mod loader;
+mod memory;
+mod operation;
+mod profile;
+mod program;
+mod search;
+mod selftest;
+mod snapshot;
+pub mod symbolic;
+mod threaded;
+mod trace;
+
+pub use ascii::{AsciiOutput, AsciiProgram, AsciiStop};
+pub use error::{IntcodeError, IntcodeErrorKind};
+pub use io::{InputFn, InputSource, OutputFn, OutputSink, StdinInput, StdoutOutput};
+pub use loader::{LoadError, ProgramLoader};
+pub use memory::{DenseMemory, Memory, SparseMemory};
+pub use profile::Profiler;
+pub use program::{IntcodeProgram, RunStatus};
+pub use search::{default_threads, InputSearch, SearchTooLarge, DEFAULT_SEARCH_BUDGET};
+pub use selftest::{run_self_test, Check, SelfTestError, SelfTestOutput};
+pub use snapshot::Snapshot;
+pub use threaded::ProgramThread;
+pub use trace::{TraceEvent, TraceFormat, TraceWriter, Tracer};
@@ -0,0 +1,188 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::vec::Vec;
+
+/// Error raised while loading an Intcode program
+#[derive(Debug)]
+pub enum LoadError {
+  /// Program source could not be read
+  Io(io::Error),
+  /// Program source contains no values
+  Empty,
+  /// Token at the given index (0-based) is not a valid integer
+  InvalidToken { index: usize, token: String },
+  /// Saved snapshot is malformed at the given line (1-based)
+  InvalidSnapshot { line: usize },
+}
+
+impl fmt::Display for LoadError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      LoadError::Io(e) => write!(f, "could not read program: {}", e),
+      LoadError::Empty => write!(f, "program contains no values"),
+      LoadError::InvalidToken { index, token } => {
+        write!(f, "invalid value {:?} at position {}", token, index)
+      }
+      LoadError::InvalidSnapshot { line } => write!(f, "invalid snapshot at line {}", line),
+    }
+  }
+}
+
+impl Error for LoadError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      LoadError::Io(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for LoadError {
+  fn from(e: io::Error) -> LoadError {
+    LoadError::Io(e)
+  }
+}
+
+/// Parses Intcode program source into a memory image
+///
+/// Values are separated by commas; whitespace and newlines around values and
+/// a single trailing comma are ignored. When comments are allowed, everything
+/// from `#` to the end of a line is skipped.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProgramLoader {
+  /// Whether `#` starts a comment that runs to the end of the line
+  pub allow_comments: bool,
+}
+
+impl ProgramLoader {
+  /// Creates a loader that rejects comments
+  pub fn new() -> ProgramLoader {
+    ProgramLoader {
+      allow_comments: false,
+    }
+  }
+
+  /// Creates a loader that skips `#` comments
+  pub fn with_comments() -> ProgramLoader {
+    ProgramLoader {
+      allow_comments: true,
+    }
+  }
+
+  /// Parses a memory image from program source text
+  pub fn parse(&self, data: &str) -> Result<Vec<i64>, LoadError> {
+    // strip comments from each line before splitting into tokens
+    let source: String = if self.allow_comments {
+      data
+        .lines()
+        .map(|line| match line.find('#') {
+          Some(i) => &line[..i],
+          None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+    } else {
+      data.to_owned()
+    };
+
+    let mut tokens: Vec<&str> = source.split(',').map(|t| t.trim()).collect();
+
+    // allow a single trailing comma
+    if tokens.len() > 1 && tokens[tokens.len() - 1].is_empty() {
+      tokens.pop();
+    }
+    if tokens.len() == 1 && tokens[0].is_empty() {
+      return Err(LoadError::Empty);
+    }
+
+    tokens
+      .iter()
+      .enumerate()
+      .map(|(index, token)| {
+        token.parse::<i64>().map_err(|_| LoadError::InvalidToken {
+          index,
+          token: (*token).to_owned(),
+        })
+      })
+      .collect()
+  }
+
+  /// Reads and parses a memory image from the given reader
+  pub fn read<R: Read>(&self, mut reader: R) -> Result<Vec<i64>, LoadError> {
+    let mut data = String::new();
+    reader.read_to_string(&mut data)?;
+    self.parse(&data)
+  }
+
+  /// Reads and parses a memory image from the file at the given path
+  pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Vec<i64>, LoadError> {
+    self.read(File::open(path)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn parse_with_whitespace() {
+    let loader = ProgramLoader::new();
+    assert_eq!(loader.parse("1,0,0,0,99\n").unwrap(), vec![1, 0, 0, 0, 99]);
+    assert_eq!(
+      loader.parse(" 1, 0 ,0,\n0,\r\n99,\n").unwrap(),
+      vec![1, 0, 0, 0, 99]
+    );
+    assert_eq!(loader.parse("104,-5,99").unwrap(), vec![104, -5, 99]);
+  }
+
+  #[test]
+  fn parse_with_comments() {
+    let source = "# add two numbers\n1101,2,3,5, # result in 5\n99,0\n";
+    assert_eq!(
+      ProgramLoader::with_comments().parse(source).unwrap(),
+      vec![1101, 2, 3, 5, 99, 0]
+    );
+
+    // comments are rejected unless enabled
+    match ProgramLoader::new().parse(source) {
+      Err(LoadError::InvalidToken { index: 0, .. }) => (),
+      other => panic!("unexpected result: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn parse_invalid_token() {
+    match ProgramLoader::new().parse("1,0,x0,0,99") {
+      Err(LoadError::InvalidToken { index, token }) => {
+        assert_eq!(index, 2);
+        assert_eq!(token, "x0");
+      }
+      other => panic!("unexpected result: {:?}", other),
+    }
+
+    // empty values are only allowed at the end
+    match ProgramLoader::new().parse("1,,99") {
+      Err(LoadError::InvalidToken { index: 1, .. }) => (),
+      other => panic!("unexpected result: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn parse_empty() {
+    assert!(matches!(
+      ProgramLoader::new().parse(" \n"),
+      Err(LoadError::Empty)
+    ));
+  }
+
+  #[test]
+  fn read_from_reader() {
+    let data: &[u8] = b"3,0,4,0,99\n";
+    assert_eq!(
+      ProgramLoader::new().read(data).unwrap(),
+      vec![3, 0, 4, 0, 99]
+    );
+  }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+
+/// Backing store for Intcode program memory
+///
+/// Memory is unbounded and every address that has not been written reads as 0.
+pub trait Memory {
+  /// Creates memory holding the given image starting at address 0
+  fn from_image(image: &[i64]) -> Self;
+  /// Retrieves the value at the given address
+  fn get(&self, address: usize) -> i64;
+  /// Stores a value at the given address
+  fn set(&mut self, address: usize, value: i64);
+  /// Lists every non-zero cell as (address, value), ordered by address
+  fn cells(&self) -> Vec<(usize, i64)>;
+}
+
+/// Number of cells kept in the contiguous part of DenseMemory
+pub(crate) const DENSE_LIMIT: usize = 1 << 20;
+
+/// Contiguous memory that grows on demand
+///
+/// Addresses below 2^20 live in a vector that is extended when written to;
+/// writes beyond that fall back to a map so stray far addresses cannot
+/// exhaust the host's memory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DenseMemory {
+  cells: Vec<i64>,
+  far: HashMap<usize, i64>,
+}
+
+impl Memory for DenseMemory {
+  fn from_image(image: &[i64]) -> DenseMemory {
+    let mut memory = DenseMemory::default();
+    for (address, value) in image.iter().enumerate() {
+      memory.set(address, *value);
+    }
+    memory
+  }
+
+  #[inline]
+  fn get(&self, address: usize) -> i64 {
+    match self.cells.get(address) {
+      Some(value) => *value,
+      None if address < DENSE_LIMIT => 0,
+      None => self.far.get(&address).copied().unwrap_or(0),
+    }
+  }
+
+  #[inline]
+  fn set(&mut self, address: usize, value: i64) {
+    if address < self.cells.len() {
+      self.cells[address] = value;
+    } else if address < DENSE_LIMIT {
+      self.cells.resize(address + 1, 0);
+      self.cells[address] = value;
+    } else {
+      self.far.insert(address, value);
+    }
+  }
+
+  fn cells(&self) -> Vec<(usize, i64)> {
+    let mut cells: Vec<(usize, i64)> = self
+      .cells
+      .iter()
+      .copied()
+      .enumerate()
+      .chain(self.far.iter().map(|(a, v)| (*a, *v)))
+      .filter(|(_, v)| *v != 0)
+      .collect();
+    cells.sort_unstable();
+    cells
+  }
+}
+
+/// Memory stored as a map from address to value
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseMemory {
+  cells: HashMap<usize, i64>,
+}
+
+impl Memory for SparseMemory {
+  fn from_image(image: &[i64]) -> SparseMemory {
+    SparseMemory {
+      cells: image.iter().copied().enumerate().collect(),
+    }
+  }
+
+  fn get(&self, address: usize) -> i64 {
+    self.cells.get(&address).copied().unwrap_or(0)
+  }
+
+  fn set(&mut self, address: usize, value: i64) {
+    self.cells.insert(address, value);
+  }
+
+  fn cells(&self) -> Vec<(usize, i64)> {
+    let mut cells: Vec<(usize, i64)> = self
+      .cells
+      .iter()
+      .filter(|(_, v)| **v != 0)
+      .map(|(a, v)| (*a, *v))
+      .collect();
+    cells.sort_unstable();
+    cells
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn check_backend<M: Memory>() {
+    let mut memory = M::from_image(&[1, 2, 3]);
+    assert_eq!(memory.get(0), 1);
+    assert_eq!(memory.get(2), 3);
+
+    // unwritten cells read as zero
+    assert_eq!(memory.get(3), 0);
+    assert_eq!(memory.get(5000), 0);
+    assert_eq!(memory.get(usize::MAX), 0);
+
+    // writes anywhere are kept
+    memory.set(1, -7);
+    memory.set(5000, 8);
+    memory.set(DENSE_LIMIT + 12, 9);
+    assert_eq!(memory.get(1), -7);
+    assert_eq!(memory.get(4999), 0);
+    assert_eq!(memory.get(5000), 8);
+    assert_eq!(memory.get(DENSE_LIMIT + 12), 9);
+
+    // only non-zero cells are listed
+    memory.set(2, 0);
+    assert_eq!(
+      memory.cells(),
+      vec![(0, 1), (1, -7), (5000, 8), (DENSE_LIMIT + 12, 9)]
+    );
+  }
+
+  #[test]
+  fn dense_memory() {
+    check_backend::<DenseMemory>();
+
+    // far writes do not grow the contiguous part
+    let mut memory = DenseMemory::default();
+    memory.set(usize::MAX, 1);
+    assert!(memory.cells.is_empty());
+    assert_eq!(memory.get(usize::MAX), 1);
+  }
+
+  #[test]
+  fn sparse_memory() {
+    check_backend::<SparseMemory>();
+  }
+}
@@ -0,0 +1,479 @@
+use crate::error::{IntcodeError, IntcodeErrorKind};
+use crate::io::{InputSource, OutputSink};
+use crate::memory::Memory;
+use crate::program::{IntcodeProgram, RunStatus};
+use std::vec::Vec;
+
+/// Decoded Intcode instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct IntcodeOperation {
+  /// Opcode of current operation
+  ///
+  /// Add: 1;
+  /// Multiply: 2;
+  /// Get input: 3;
+  /// Print value: 4;
+  /// Jump-if-true: 5;
+  /// Jump-if-false: 6;
+  /// Less than: 7;
+  /// Equals: 8;
+  /// Adjust relative base: 9;
+  /// Exit: 99
+  pub(crate) opcode: u8,
+  /// Length of current operation
+  ///
+  /// Add: 4;
+  /// Multiply: 4;
+  /// Get input: 2;
+  /// Print value: 2;
+  /// Jump-if-true: 3;
+  /// Jump-if-false: 3;
+  /// Less than: 4;
+  /// Equals: 4;
+  /// Adjust relative base: 2;
+  /// Exit: 1
+  pub(crate) len: usize,
+  /// Modes of parameters for current operation
+  ///
+  /// Position mode: 0;
+  /// Immediate mode: 1;
+  /// Relative mode: 2
+  pub(crate) modes: [u8; 3],
+}
+
+/// Parameters of an instruction resolved against the current machine state
+pub(crate) struct ResolvedOperands {
+  /// Value read for each read parameter, target address for each write parameter
+  pub(crate) values: Vec<i64>,
+  /// Addresses read by the read parameters
+  pub(crate) reads: Vec<usize>,
+  /// Address written by the write parameter, if any
+  pub(crate) write: Option<usize>,
+}
+
+/// Adds a parameter value to the relative base, rejecting results outside of memory
+fn relative_addr(mem_value: i64, rel_base: usize) -> Result<i64, IntcodeErrorKind> {
+  mem_value
+    .checked_add(rel_base as i64)
+    .ok_or(IntcodeErrorKind::AddressOverflow)
+}
+
+/// Converts a computed address into a memory index, rejecting negative addresses
+fn checked_addr(addr: i64) -> Result<usize, IntcodeErrorKind> {
+  if addr < 0 {
+    return Err(IntcodeErrorKind::NegativeAddress(addr));
+  }
+  Ok(addr as usize)
+}
+
+/// Gets address of parameter based on parameter mode
+fn get_read_addr(
+  mem_value: i64,
+  mode: u8,
+  base: usize,
+  rel_base: usize,
+) -> Result<usize, IntcodeErrorKind> {
+  match mode {
+    // position mode
+    0 => checked_addr(mem_value),
+    // immediate mode
+    1 => Ok(base),
+    // relative mode
+    2 => checked_addr(relative_addr(mem_value, rel_base)?),
+    _ => Err(IntcodeErrorKind::InvalidMode),
+  }
+}
+
+/// Gets address of write parameter based on parameter mode
+fn get_write_addr(mem_value: i64, mode: u8, rel_base: usize) -> Result<usize, IntcodeErrorKind> {
+  match mode {
+    // position mode
+    0 => checked_addr(mem_value),
+    // immediate mode cannot be written to
+    1 => Err(IntcodeErrorKind::ImmediateWrite),
+    // relative mode
+    2 => checked_addr(relative_addr(mem_value, rel_base)?),
+    _ => Err(IntcodeErrorKind::InvalidMode),
+  }
+}
+
+impl IntcodeOperation {
+  /// Creates a new IntcodeOperation object from the given operation value
+  pub(crate) fn new(op: i64) -> Result<IntcodeOperation, IntcodeErrorKind> {
+    if op < 0 {
+      return Err(IntcodeErrorKind::InvalidOpcode);
+    }
+
+    // opcode is stored in the two lowest decimal digits
+    let code = (op % 100) as u8;
+    let len = match code {
+      1 | 2 | 7 | 8 => 4,
+      5 | 6 => 3,
+      3 | 4 | 9 => 2,
+      99 => 1,
+      _ => return Err(IntcodeErrorKind::InvalidOpcode),
+    };
+
+    // parameter modes follow from right to left
+    let modes = [
+      (op / 100 % 10) as u8,
+      (op / 1_000 % 10) as u8,
+      (op / 10_000 % 10) as u8,
+    ];
+
+    Ok(IntcodeOperation {
+      opcode: code,
+      len,
+      modes,
+    })
+  }
+
+  /// Returns whether the parameter at the given index is a write target
+  pub(crate) fn writes_param(&self, index: usize) -> bool {
+    match self.opcode {
+      1 | 2 | 7 | 8 => index == 2,
+      3 => index == 0,
+      _ => false,
+    }
+  }
+
+  /// Resolves each parameter without executing the instruction
+  ///
+  /// Read parameters resolve to the value read and write parameters to the
+  /// target address. Returns `None` if any parameter cannot be resolved.
+  pub(crate) fn resolve_operands<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &IntcodeProgram<I, O, M>,
+  ) -> Option<ResolvedOperands> {
+    let mut resolved = ResolvedOperands {
+      values: Vec::with_capacity(self.len - 1),
+      reads: Vec::with_capacity(self.len - 1),
+      write: None,
+    };
+    for index in 0..self.len - 1 {
+      let param_addr = prg.instruction_pointer + index + 1;
+      let mem_value = prg.get_value(param_addr);
+      if self.writes_param(index) {
+        let addr = get_write_addr(mem_value, self.modes[index], prg.relative_base).ok()?;
+        resolved.write = Some(addr);
+        resolved.values.push(addr as i64);
+      } else {
+        let addr =
+          get_read_addr(mem_value, self.modes[index], param_addr, prg.relative_base).ok()?;
+        resolved.reads.push(addr);
+        resolved.values.push(prg.get_value(addr));
+      }
+    }
+    Some(resolved)
+  }
+
+  /// Reads the value of the parameter at the given index
+  fn read_param<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+    index: usize,
+  ) -> Result<i64, IntcodeError> {
+    let param_addr = prg.instruction_pointer + index + 1;
+    let addr = get_read_addr(
+      prg.get_value(param_addr),
+      self.modes[index],
+      param_addr,
+      prg.relative_base,
+    )
+    .map_err(|kind| prg.fault(kind, Some(index), Some(self.modes[index])))?;
+    Ok(prg.get_value(addr))
+  }
+
+  /// Stores a value at the address given by the parameter at the given index
+  fn write_param<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+    index: usize,
+    value: i64,
+  ) -> Result<(), IntcodeError> {
+    let param_addr = prg.instruction_pointer + index + 1;
+    let addr = get_write_addr(
+      prg.get_value(param_addr),
+      self.modes[index],
+      prg.relative_base,
+    )
+    .map_err(|kind| prg.fault(kind, Some(index), Some(self.modes[index])))?;
+    prg.set_value(addr, value);
+    Ok(())
+  }
+
+  /// Adds two parameters together and stores sum in program memory
+  fn op_add<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let op_l = self.read_param(prg, 0)?;
+    let op_r = self.read_param(prg, 1)?;
+    let sum = op_l
+      .checked_add(op_r)
+      .ok_or_else(|| prg.fault(IntcodeErrorKind::ArithmeticOverflow, None, None))?;
+    self.write_param(prg, 2, sum)?;
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Multiplies two parameters together and store product in program memory
+  fn op_mult<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let op_l = self.read_param(prg, 0)?;
+    let op_r = self.read_param(prg, 1)?;
+    let product = op_l
+      .checked_mul(op_r)
+      .ok_or_else(|| prg.fault(IntcodeErrorKind::ArithmeticOverflow, None, None))?;
+    self.write_param(prg, 2, product)?;
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Receives integer input and stores in program memory
+  ///
+  /// Leaves the instruction pointer in place if no input is available.
+  fn op_input<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<RunStatus, IntcodeError> {
+    let value = match prg.input.read() {
+      Some(value) => value,
+      None => return Ok(RunStatus::NeedsInput),
+    };
+    self.write_param(prg, 0, value)?;
+    prg.instruction_pointer += self.len;
+    Ok(RunStatus::Running)
+  }
+
+  /// Retrieves value from program memory and outputs it
+  fn op_output<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<RunStatus, IntcodeError> {
+    let value = self.read_param(prg, 0)?;
+    prg.output.write(value);
+    prg.instruction_pointer += self.len;
+    Ok(RunStatus::Output(value))
+  }
+
+  /// Validates the address given by the second parameter of a jump operation
+  fn jump_target<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &IntcodeProgram<I, O, M>,
+    target: i64,
+  ) -> Result<usize, IntcodeError> {
+    checked_addr(target).map_err(|kind| prg.fault(kind, Some(1), Some(self.modes[1])))
+  }
+
+  /// Jumps to address given by second parameter if first parameter is non-zero
+  fn op_jump_true<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let op_c = self.read_param(prg, 0)?;
+    let op_j = self.read_param(prg, 1)?;
+    if op_c != 0 {
+      return self.jump_target(prg, op_j);
+    }
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Jumps to address given by second parameter if first parameter is zero
+  fn op_jump_false<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let op_c = self.read_param(prg, 0)?;
+    let op_j = self.read_param(prg, 1)?;
+    if op_c == 0 {
+      return self.jump_target(prg, op_j);
+    }
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Stores 1 in program memory if first parameter is less than second parameter; otherwise 0
+  fn op_less_than<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let op_l = self.read_param(prg, 0)?;
+    let op_r = self.read_param(prg, 1)?;
+    self.write_param(prg, 2, if op_l < op_r { 1 } else { 0 })?;
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Stores 1 in program memory if first two parameters are equal; otherwise 0
+  fn op_equals<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let op_l = self.read_param(prg, 0)?;
+    let op_r = self.read_param(prg, 1)?;
+    self.write_param(prg, 2, if op_l == op_r { 1 } else { 0 })?;
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Adjusts the program's relative base address
+  fn op_adj_rel_base<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<usize, IntcodeError> {
+    let val_adj = self.read_param(prg, 0)?;
+    let new_base = relative_addr(val_adj, prg.relative_base)
+      .and_then(|base| {
+        if base < 0 {
+          Err(IntcodeErrorKind::RelativeBaseUnderflow(base))
+        } else {
+          Ok(base as usize)
+        }
+      })
+      .map_err(|kind| prg.fault(kind, Some(0), Some(self.modes[0])))?;
+    prg.relative_base = new_base;
+    Ok(prg.instruction_pointer + self.len)
+  }
+
+  /// Performs the current Intcode operation using the Intcode program memory
+  pub(crate) fn perform<I: InputSource, O: OutputSink, M: Memory>(
+    &self,
+    prg: &mut IntcodeProgram<I, O, M>,
+  ) -> Result<RunStatus, IntcodeError> {
+    let new_pos = match self.opcode {
+      1 => self.op_add(prg)?,
+      2 => self.op_mult(prg)?,
+      3 => return self.op_input(prg),
+      4 => return self.op_output(prg),
+      5 => self.op_jump_true(prg)?,
+      6 => self.op_jump_false(prg)?,
+      7 => self.op_less_than(prg)?,
+      8 => self.op_equals(prg)?,
+      9 => self.op_adj_rel_base(prg)?,
+      99 => {
+        prg.halted = true;
+        return Ok(RunStatus::Halted);
+      }
+      _ => return Err(prg.fault(IntcodeErrorKind::InvalidOpcode, None, None)),
+    };
+
+    // update instruction pointer
+    prg.instruction_pointer = new_pos;
+    Ok(RunStatus::Running)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn decode_operations() {
+    assert_eq!(
+      IntcodeOperation::new(1002),
+      Ok(IntcodeOperation {
+        opcode: 2,
+        len: 4,
+        modes: [0, 1, 0],
+      })
+    );
+    assert_eq!(
+      IntcodeOperation::new(21107),
+      Ok(IntcodeOperation {
+        opcode: 7,
+        len: 4,
+        modes: [1, 1, 2],
+      })
+    );
+    assert_eq!(
+      IntcodeOperation::new(204),
+      Ok(IntcodeOperation {
+        opcode: 4,
+        len: 2,
+        modes: [2, 0, 0],
+      })
+    );
+    assert_eq!(
+      IntcodeOperation::new(99),
+      Ok(IntcodeOperation {
+        opcode: 99,
+        len: 1,
+        modes: [0, 0, 0],
+      })
+    );
+  }
+
+  #[test]
+  fn read_addr_modes() {
+    // position mode uses the parameter as an address
+    assert_eq!(get_read_addr(7, 0, 1, 0), Ok(7));
+    assert_eq!(get_read_addr(7, 0, 1, 10), Ok(7));
+    assert_eq!(
+      get_read_addr(-1, 0, 1, 0),
+      Err(IntcodeErrorKind::NegativeAddress(-1))
+    );
+
+    // immediate mode uses the parameter's own address
+    assert_eq!(get_read_addr(-5, 1, 1, 0), Ok(1));
+    assert_eq!(get_read_addr(123, 1, 2, 50), Ok(2));
+
+    // relative mode offsets the parameter by the relative base
+    assert_eq!(get_read_addr(7, 2, 1, 10), Ok(17));
+    assert_eq!(get_read_addr(-10, 2, 1, 10), Ok(0));
+    assert_eq!(
+      get_read_addr(-11, 2, 1, 10),
+      Err(IntcodeErrorKind::NegativeAddress(-1))
+    );
+    assert_eq!(
+      get_read_addr(i64::MAX, 2, 1, 1),
+      Err(IntcodeErrorKind::AddressOverflow)
+    );
+
+    assert_eq!(
+      get_read_addr(7, 3, 1, 0),
+      Err(IntcodeErrorKind::InvalidMode)
+    );
+  }
+
+  #[test]
+  fn write_addr_modes() {
+    // position mode
+    assert_eq!(get_write_addr(7, 0, 10), Ok(7));
+    assert_eq!(
+      get_write_addr(-1, 0, 10),
+      Err(IntcodeErrorKind::NegativeAddress(-1))
+    );
+
+    // immediate mode cannot be written to
+    assert_eq!(
+      get_write_addr(7, 1, 10),
+      Err(IntcodeErrorKind::ImmediateWrite)
+    );
+
+    // relative mode
+    assert_eq!(get_write_addr(-3, 2, 10), Ok(7));
+    assert_eq!(
+      get_write_addr(-13, 2, 10),
+      Err(IntcodeErrorKind::NegativeAddress(-3))
+    );
+
+    assert_eq!(get_write_addr(7, 9, 10), Err(IntcodeErrorKind::InvalidMode));
+  }
+
+  #[test]
+  fn decode_invalid_opcodes() {
+    assert_eq!(
+      IntcodeOperation::new(0),
+      Err(IntcodeErrorKind::InvalidOpcode)
+    );
+    assert_eq!(
+      IntcodeOperation::new(42),
+      Err(IntcodeErrorKind::InvalidOpcode)
+    );
+    assert_eq!(
+      IntcodeOperation::new(198),
+      Err(IntcodeErrorKind::InvalidOpcode)
+    );
+    assert_eq!(
+      IntcodeOperation::new(-1),
+      Err(IntcodeErrorKind::InvalidOpcode)
+    );
+  }
+}
@@ -0,0 +1,170 @@
+use crate::disasm;
+use crate::trace::{TraceEvent, Tracer};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::vec::Vec;
+
+/// Execution and memory access counts gathered from a traced run
+///
+/// Pass a Profiler to `IntcodeProgram::run_traced` (or `step_traced`) and
+/// call `report` afterwards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profiler {
+  /// Total number of instructions executed
+  pub instructions: u64,
+  /// Executions per opcode
+  pub opcodes: BTreeMap<u8, u64>,
+  /// Executions per instruction address
+  pub addresses: BTreeMap<usize, u64>,
+  /// Parameters resolved per mode (position, immediate, relative)
+  pub modes: [u64; 3],
+  /// Operand reads per memory cell
+  pub reads: BTreeMap<usize, u64>,
+  /// Writes per memory cell
+  pub writes: BTreeMap<usize, u64>,
+}
+
+impl Tracer for Profiler {
+  fn record(&mut self, event: &TraceEvent) {
+    self.instructions += 1;
+    *self.opcodes.entry(event.opcode).or_insert(0) += 1;
+    *self.addresses.entry(event.ip).or_insert(0) += 1;
+    for mode in event.modes.iter() {
+      self.modes[*mode as usize] += 1;
+    }
+    for addr in event.reads.iter() {
+      *self.reads.entry(*addr).or_insert(0) += 1;
+    }
+    if let Some((addr, _)) = event.write {
+      *self.writes.entry(addr).or_insert(0) += 1;
+    }
+  }
+}
+
+/// Sorts counts from highest to lowest, breaking ties by key
+fn sorted<K: Copy + Ord>(counts: &BTreeMap<K, u64>) -> Vec<(K, u64)> {
+  let mut counts: Vec<(K, u64)> = counts.iter().map(|(k, v)| (*k, *v)).collect();
+  counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+  counts
+}
+
+impl Profiler {
+  /// Creates an empty profiler
+  pub fn new() -> Profiler {
+    Profiler::default()
+  }
+
+  /// Returns executions per opcode, most frequent first
+  pub fn hot_opcodes(&self) -> Vec<(u8, u64)> {
+    sorted(&self.opcodes)
+  }
+
+  /// Returns executions per address, most frequent first
+  pub fn hot_addresses(&self) -> Vec<(usize, u64)> {
+    sorted(&self.addresses)
+  }
+
+  /// Returns reads per memory cell, most frequent first
+  pub fn hot_reads(&self) -> Vec<(usize, u64)> {
+    sorted(&self.reads)
+  }
+
+  /// Returns writes per memory cell, most frequent first
+  pub fn hot_writes(&self) -> Vec<(usize, u64)> {
+    sorted(&self.writes)
+  }
+
+  /// Renders a report of all counts, listing at most `limit` addresses per section
+  pub fn report(&self, limit: usize) -> String {
+    let percent = |count: u64, total: u64| {
+      if total == 0 {
+        0.0
+      } else {
+        count as f64 * 100.0 / total as f64
+      }
+    };
+    let mut out = String::new();
+    writeln!(out, "instructions executed: {}", self.instructions).unwrap();
+
+    writeln!(out, "\nopcodes:").unwrap();
+    for (opcode, count) in self.hot_opcodes() {
+      writeln!(
+        out,
+        "  {:<4} {:>12} {:>6.2}%",
+        disasm::mnemonic(opcode).unwrap_or("???"),
+        count,
+        percent(count, self.instructions)
+      )
+      .unwrap();
+    }
+
+    writeln!(out, "\nparameter modes:").unwrap();
+    let params: u64 = self.modes.iter().sum();
+    for (name, count) in ["position", "immediate", "relative"]
+      .iter()
+      .zip(self.modes.iter())
+    {
+      writeln!(
+        out,
+        "  {:<9} {:>12} {:>6.2}%",
+        name,
+        count,
+        percent(*count, params)
+      )
+      .unwrap();
+    }
+
+    let sections = [
+      ("hot addresses", self.hot_addresses()),
+      ("memory reads", self.hot_reads()),
+      ("memory writes", self.hot_writes()),
+    ];
+    for (title, counts) in sections.iter() {
+      writeln!(
+        out,
+        "\n{} (top {} of {}):",
+        title,
+        limit.min(counts.len()),
+        counts.len()
+      )
+      .unwrap();
+      for (addr, count) in counts.iter().take(limit) {
+        writeln!(out, "  {:>8} {:>12}", addr, count).unwrap();
+      }
+    }
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::program::{IntcodeProgram, RunStatus};
+  #[test]
+  fn count_loop() {
+    // count cell 9 down from 3 to 0, then halt
+    let mut prg = IntcodeProgram::new("1001,9,-1,9,1005,9,0,99,0,3", vec![]).unwrap();
+    let mut profiler = Profiler::new();
+    assert_eq!(prg.run_traced(&mut profiler), Ok(RunStatus::Halted));
+
+    assert_eq!(profiler.instructions, 7);
+    assert_eq!(profiler.hot_opcodes(), vec![(1, 3), (5, 3), (99, 1)]);
+    assert_eq!(profiler.hot_addresses(), vec![(0, 3), (4, 3), (7, 1)]);
+    assert_eq!(profiler.modes, [9, 6, 0]);
+    assert_eq!(profiler.hot_writes(), vec![(9, 3)]);
+    assert_eq!(profiler.hot_reads()[0], (9, 6));
+  }
+
+  #[test]
+  fn sorted_report() {
+    let mut prg = IntcodeProgram::new("1001,9,-1,9,1005,9,0,99,0,3", vec![]).unwrap();
+    let mut profiler = Profiler::new();
+    prg.run_traced(&mut profiler).unwrap();
+
+    let report = profiler.report(1);
+    assert!(report.starts_with("instructions executed: 7\n"));
+    assert!(report.contains("  add             3  42.86%\n"));
+    assert!(report.contains("hot addresses (top 1 of 3):\n         0            3\n\n"));
+    assert!(report.contains("memory writes (top 1 of 1):\n         9            3\n"));
+  }
+}
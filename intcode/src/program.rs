@@ -0,0 +1,676 @@
+use crate::error::{IntcodeError, IntcodeErrorKind};
+use crate::io::{InputSource, OutputSink};
+use crate::loader::{LoadError, ProgramLoader};
+use crate::memory::{DenseMemory, Memory, DENSE_LIMIT};
+use crate::operation::IntcodeOperation;
+use crate::trace::{TraceEvent, Tracer};
+use std::collections::VecDeque;
+use std::time::Instant;
+use std::vec::Vec;
+
+/// State of an IntcodeProgram after executing one or more instructions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunStatus {
+  /// Instruction was executed and the program can continue
+  Running,
+  /// Program is waiting on an input instruction with no input available
+  NeedsInput,
+  /// Program emitted the given value
+  Output(i64),
+  /// Program reached the exit instruction
+  Halted,
+  /// Instruction budget or deadline ran out before the next instruction
+  BudgetExhausted,
+}
+
+/// Number of instructions executed between checks of the deadline
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// Intcode computer reading input from `I`, writing output to `O`,
+/// and storing its memory in `M`
+///
+/// By default, input is taken from a queue of provided values, output is
+/// collected in a vector, and memory is a contiguous growable vector.
+#[derive(Debug)]
+pub struct IntcodeProgram<I = VecDeque<i64>, O = Vec<i64>, M = DenseMemory> {
+  pub(crate) memory: M,
+  pub(crate) relative_base: usize,
+  pub(crate) instruction_pointer: usize,
+  pub input: I,
+  pub output: O,
+  pub(crate) halted: bool,
+  /// Decoded instructions by address, if caching is enabled
+  pub(crate) decode_cache: Option<Vec<Option<IntcodeOperation>>>,
+  /// Number of instructions executed so far
+  pub(crate) instruction_count: u64,
+  /// Number of instructions left to execute, if limited
+  budget: Option<u64>,
+  /// Time after which execution stops, if limited
+  deadline: Option<Instant>,
+}
+
+impl IntcodeProgram {
+  /// Creates a new IntcodeProgram object using the given program data
+  ///
+  /// Input is taken from the given vector and output is collected in `output`.
+  pub fn new(data: &str, input: Vec<i64>) -> Result<IntcodeProgram, LoadError> {
+    let image = ProgramLoader::new().parse(data)?;
+    Ok(IntcodeProgram::from_image(&image, input))
+  }
+
+  /// Creates a new IntcodeProgram object from an already parsed memory image
+  pub fn from_image(image: &[i64], input: Vec<i64>) -> IntcodeProgram {
+    IntcodeProgram::with_io(image, input.into_iter().collect(), Vec::new())
+  }
+
+  /// Queues a value for the program's next input instruction
+  pub fn feed(&mut self, value: i64) {
+    self.input.push_back(value);
+  }
+
+  /// Removes and returns all values emitted so far
+  pub fn take_output(&mut self) -> Vec<i64> {
+    std::mem::take(&mut self.output)
+  }
+}
+
+impl<I: InputSource, O: OutputSink> IntcodeProgram<I, O> {
+  /// Creates a new IntcodeProgram object from a memory image
+  /// using the given input source and output sink
+  pub fn with_io(image: &[i64], input: I, output: O) -> IntcodeProgram<I, O> {
+    IntcodeProgram::with_memory(DenseMemory::from_image(image), input, output)
+  }
+}
+
+impl<I, O, M> IntcodeProgram<I, O, M> {
+  /// Moves the machine onto a new input source and output sink
+  ///
+  /// Returns the machine along with its old input source and output sink.
+  pub fn replace_io<I2, O2>(self, input: I2, output: O2) -> (IntcodeProgram<I2, O2, M>, I, O) {
+    let prg = IntcodeProgram {
+      memory: self.memory,
+      relative_base: self.relative_base,
+      instruction_pointer: self.instruction_pointer,
+      input,
+      output,
+      halted: self.halted,
+      decode_cache: self.decode_cache,
+      instruction_count: self.instruction_count,
+      budget: self.budget,
+      deadline: self.deadline,
+    };
+    (prg, self.input, self.output)
+  }
+}
+
+impl<I: InputSource, O: OutputSink, M: Memory> IntcodeProgram<I, O, M> {
+  /// Creates a new IntcodeProgram object from already initialized memory
+  /// using the given input source and output sink
+  pub fn with_memory(memory: M, input: I, output: O) -> IntcodeProgram<I, O, M> {
+    IntcodeProgram {
+      memory,
+      relative_base: 0,
+      instruction_pointer: 0,
+      input,
+      output,
+      halted: false,
+      decode_cache: None,
+      instruction_count: 0,
+      budget: None,
+      deadline: None,
+    }
+  }
+
+  /// Retrieves value from program memory
+  pub fn get_value(&self, address: usize) -> i64 {
+    self.memory.get(address)
+  }
+
+  /// Stores value in program memory
+  pub fn set_value(&mut self, address: usize, value: i64) {
+    self.memory.set(address, value);
+
+    // drop any cached decoding of the overwritten instruction
+    if let Some(cache) = self.decode_cache.as_mut() {
+      if let Some(entry) = cache.get_mut(address) {
+        *entry = None;
+      }
+    }
+  }
+
+  /// Caches decoded instructions by address to skip decoding on later visits
+  ///
+  /// Cached instructions are invalidated when the program writes to them.
+  /// Like DenseMemory, only addresses below 2^20 are cached; instructions
+  /// beyond that are decoded on every visit.
+  pub fn enable_decode_cache(&mut self) {
+    if self.decode_cache.is_none() {
+      self.decode_cache = Some(Vec::new());
+    }
+  }
+
+  /// Builds an error describing a failure of the current instruction
+  pub(crate) fn fault(
+    &self,
+    kind: IntcodeErrorKind,
+    parameter: Option<usize>,
+    mode: Option<u8>,
+  ) -> IntcodeError {
+    IntcodeError {
+      kind,
+      instruction_pointer: self.instruction_pointer,
+      opcode: self.get_value(self.instruction_pointer),
+      parameter,
+      mode,
+      relative_base: self.relative_base,
+    }
+  }
+
+  /// Decodes the operation at the current instruction pointer
+  pub(crate) fn current_operation(&mut self) -> Result<IntcodeOperation, IntcodeError> {
+    let ip = self.instruction_pointer;
+    if let Some(Some(op)) = self.decode_cache.as_ref().and_then(|cache| cache.get(ip)) {
+      return Ok(*op);
+    }
+
+    let op =
+      IntcodeOperation::new(self.get_value(ip)).map_err(|kind| self.fault(kind, None, None))?;
+    if let Some(cache) = self.decode_cache.as_mut().filter(|_| ip < DENSE_LIMIT) {
+      if cache.len() <= ip {
+        cache.resize(ip + 1, None);
+      }
+      cache[ip] = Some(op);
+    }
+    Ok(op)
+  }
+
+  /// Returns the address of the next instruction to execute
+  pub fn instruction_pointer(&self) -> usize {
+    self.instruction_pointer
+  }
+
+  /// Returns the current relative base
+  pub fn relative_base(&self) -> usize {
+    self.relative_base
+  }
+
+  /// Returns the number of instructions executed so far
+  pub fn instruction_count(&self) -> u64 {
+    self.instruction_count
+  }
+
+  /// Limits how many more instructions may execute, or removes the limit
+  ///
+  /// Once the budget is spent, running stops with `BudgetExhausted` and the
+  /// program can be resumed by setting a new budget.
+  pub fn set_instruction_budget(&mut self, budget: Option<u64>) {
+    self.budget = budget;
+  }
+
+  /// Returns the number of instructions left in the budget, if limited
+  pub fn instruction_budget(&self) -> Option<u64> {
+    self.budget
+  }
+
+  /// Stops execution once the given time has passed, or removes the deadline
+  ///
+  /// The deadline is checked every 1024 instructions. Once it has passed,
+  /// running stops with `BudgetExhausted` and the program can be resumed by
+  /// setting a later deadline.
+  pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+    self.deadline = deadline;
+  }
+
+  /// Returns true if the budget or deadline forbids executing another instruction
+  pub(crate) fn budget_exhausted(&self) -> bool {
+    if self.budget == Some(0) {
+      return true;
+    }
+    match self.deadline {
+      Some(deadline)
+        if self
+          .instruction_count
+          .is_multiple_of(DEADLINE_CHECK_INTERVAL) =>
+      {
+        Instant::now() >= deadline
+      }
+      _ => false,
+    }
+  }
+
+  /// Counts an executed instruction against the budget
+  pub(crate) fn charge_instruction(&mut self) {
+    self.instruction_count += 1;
+    if let Some(budget) = self.budget.as_mut() {
+      *budget -= 1;
+    }
+  }
+
+  /// Returns true once the program has reached the exit instruction
+  pub fn is_halted(&self) -> bool {
+    self.halted
+  }
+
+  /// Executes a single instruction
+  pub fn step(&mut self) -> Result<RunStatus, IntcodeError> {
+    self.step_with(None)
+  }
+
+  /// Executes a single instruction, passing a record of it to the tracer if
+  /// one is given
+  ///
+  /// Nothing is recorded for instructions that fail or wait for input.
+  pub(crate) fn step_with(
+    &mut self,
+    tracer: Option<&mut dyn Tracer>,
+  ) -> Result<RunStatus, IntcodeError> {
+    if self.halted {
+      return Ok(RunStatus::Halted);
+    }
+    if self.budget_exhausted() {
+      return Ok(RunStatus::BudgetExhausted);
+    }
+    let ip = self.instruction_pointer;
+    let cur_op = self.current_operation()?;
+
+    // operands are resolved before the instruction can overwrite them
+    let resolved = match tracer {
+      Some(_) => cur_op.resolve_operands(self),
+      None => None,
+    };
+
+    let status = cur_op.perform(self)?;
+    if status == RunStatus::NeedsInput {
+      return Ok(status);
+    }
+    self.charge_instruction();
+    if let (Some(tracer), Some(resolved)) = (tracer, resolved) {
+      tracer.record(&TraceEvent {
+        ip,
+        opcode: cur_op.opcode,
+        modes: cur_op.modes[..cur_op.len - 1].to_vec(),
+        operands: resolved.values,
+        reads: resolved.reads,
+        write: resolved.write.map(|addr| (addr, self.get_value(addr))),
+        relative_base: self.relative_base,
+      });
+    }
+    Ok(status)
+  }
+
+  /// Executes the IntcodeProgram until it halts, needs more input, or runs
+  /// out of budget
+  ///
+  /// Emitted values are passed to the output sink along the way.
+  pub fn run(&mut self) -> Result<RunStatus, IntcodeError> {
+    loop {
+      match self.step()? {
+        RunStatus::Running | RunStatus::Output(_) => continue,
+        status => return Ok(status),
+      }
+    }
+  }
+
+  /// Executes the IntcodeProgram until it emits a value, halts, needs more
+  /// input, or runs out of budget
+  pub fn run_until_output(&mut self) -> Result<RunStatus, IntcodeError> {
+    loop {
+      match self.step()? {
+        RunStatus::Running => continue,
+        status => return Ok(status),
+      }
+    }
+  }
+
+  /// Executes the IntcodeProgram to completion
+  ///
+  /// Fails with `InputExhausted` if the program waits on input that is not
+  /// available, or `BudgetExhausted` if it runs out of budget.
+  pub fn run_to_halt(&mut self) -> Result<(), IntcodeError> {
+    match self.run()? {
+      RunStatus::NeedsInput => Err(self.fault(IntcodeErrorKind::InputExhausted, None, None)),
+      RunStatus::BudgetExhausted => Err(self.fault(IntcodeErrorKind::BudgetExhausted, None, None)),
+      _ => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::io::{InputFn, OutputFn};
+  use crate::memory::SparseMemory;
+  #[test]
+  fn mult_op_with_modes() {
+    // execute program "1002,4,3,4,33"
+    let mut prg = IntcodeProgram::new("1002,4,3,4,33", vec![]).unwrap();
+
+    let expected_mem: Vec<i64> = vec![1002, 4, 3, 4, 33];
+    for (i, expected) in expected_mem.iter().enumerate() {
+      assert_eq!(prg.get_value(i), *expected);
+    }
+
+    // last value should be exit opcode
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.get_value(4), 99);
+  }
+
+  #[test]
+  fn add_op_with_negatives() {
+    // execute program "1101,100,-1,4,0"
+    let mut prg = IntcodeProgram::new("1101,100,-1,4,0", vec![]).unwrap();
+
+    let expected_mem: Vec<i64> = vec![1101, 100, -1, 4, 0];
+    for (i, expected) in expected_mem.iter().enumerate() {
+      assert_eq!(prg.get_value(i), *expected);
+    }
+
+    // last value should be exit opcode
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.get_value(4), 99);
+  }
+
+  #[test]
+  fn new_with_trailing_newline() {
+    let mut prg = IntcodeProgram::new("1,0,0,0,99\n", vec![]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.get_value(0), 2);
+
+    match IntcodeProgram::new("1,0,0,O,99", vec![]) {
+      Err(LoadError::InvalidToken { index: 3, .. }) => (),
+      other => panic!("unexpected result: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn equals_with_provided_input() {
+    // outputs 1 if input is equal to 8, else 0
+    let mut prg = IntcodeProgram::new("3,9,8,9,10,9,4,9,99,-1,8", vec![8]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![1]);
+
+    let mut prg = IntcodeProgram::new("3,3,1107,-1,8,3,4,3,99", vec![9]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![0]);
+  }
+
+  #[test]
+  fn resume_after_input() {
+    // echo two inputs back before halting
+    let mut prg = IntcodeProgram::new("3,9,4,9,3,9,4,9,99,0", vec![]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    prg.feed(5);
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    assert_eq!(prg.take_output(), vec![5]);
+    assert!(!prg.is_halted());
+    prg.feed(7);
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert!(prg.is_halted());
+    assert_eq!(prg.take_output(), vec![7]);
+
+    // halted programs stay halted
+    assert_eq!(prg.step(), Ok(RunStatus::Halted));
+  }
+
+  #[test]
+  fn step_through_program() {
+    let mut prg = IntcodeProgram::new("1101,2,3,7,4,7,99,0", vec![]).unwrap();
+    assert_eq!(prg.step(), Ok(RunStatus::Running));
+    assert_eq!(prg.step(), Ok(RunStatus::Output(5)));
+    assert_eq!(prg.step(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![5]);
+  }
+
+  #[test]
+  fn run_until_output() {
+    let mut prg = IntcodeProgram::new("104,1,104,2,99", vec![]).unwrap();
+    assert_eq!(prg.run_until_output(), Ok(RunStatus::Output(1)));
+    assert_eq!(prg.run_until_output(), Ok(RunStatus::Output(2)));
+    assert_eq!(prg.run_until_output(), Ok(RunStatus::Halted));
+  }
+
+  #[test]
+  fn invalid_opcode_error() {
+    let mut prg = IntcodeProgram::new("1101,1,1,5,42,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::InvalidOpcode);
+    assert_eq!(err.instruction_pointer, 4);
+    assert_eq!(err.opcode, 42);
+    assert_eq!(err.parameter, None);
+  }
+
+  #[test]
+  fn invalid_mode_error() {
+    let mut prg = IntcodeProgram::new("3101,1,1,5,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::InvalidMode);
+    assert_eq!(err.opcode, 3101);
+    assert_eq!(err.parameter, Some(1));
+    assert_eq!(err.mode, Some(3));
+  }
+
+  #[test]
+  fn arithmetic_overflow_error() {
+    let mut prg = IntcodeProgram::new("1101,9223372036854775807,1,0,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::ArithmeticOverflow);
+    assert_eq!(err.instruction_pointer, 0);
+    assert_eq!(err.parameter, None);
+
+    let mut prg = IntcodeProgram::new("1102,4294967296,4294967296,0,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::ArithmeticOverflow);
+  }
+
+  #[test]
+  fn immediate_write_error() {
+    let mut prg = IntcodeProgram::new("109,4,11101,1,1,5,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::ImmediateWrite);
+    assert_eq!(err.instruction_pointer, 2);
+    assert_eq!(err.parameter, Some(2));
+    assert_eq!(err.mode, Some(1));
+    assert_eq!(err.relative_base, 4);
+  }
+
+  #[test]
+  fn negative_address_error() {
+    let mut prg = IntcodeProgram::new("4,-3,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::NegativeAddress(-3));
+    assert_eq!(err.parameter, Some(0));
+  }
+
+  #[test]
+  fn negative_relative_address_error() {
+    // relative base 2, then write to rb[-3]
+    let mut prg = IntcodeProgram::new("109,2,21101,1,1,-3,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::NegativeAddress(-1));
+    assert_eq!(err.instruction_pointer, 2);
+    assert_eq!(err.parameter, Some(2));
+    assert_eq!(err.mode, Some(2));
+    assert_eq!(err.relative_base, 2);
+  }
+
+  #[test]
+  fn relative_base_underflow_error() {
+    let mut prg = IntcodeProgram::new("109,3,109,-4,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::RelativeBaseUnderflow(-1));
+    assert_eq!(err.instruction_pointer, 2);
+    assert_eq!(err.opcode, 109);
+    assert_eq!(err.parameter, Some(0));
+    assert_eq!(err.relative_base, 3);
+
+    // moving the base back down to zero is allowed
+    let mut prg = IntcodeProgram::new("109,3,109,-3,99", vec![]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+  }
+
+  #[test]
+  fn negative_jump_target_error() {
+    let mut prg = IntcodeProgram::new("1105,1,-4,99", vec![]).unwrap();
+    let err = prg.run().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::NegativeAddress(-4));
+    assert_eq!(err.parameter, Some(1));
+    assert_eq!(err.mode, Some(1));
+
+    // untaken jumps do not check their target
+    let mut prg = IntcodeProgram::new("1106,1,-4,99", vec![]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+  }
+
+  #[test]
+  fn input_exhausted_error() {
+    let mut prg = IntcodeProgram::new("3,5,3,5,99,0", vec![1]).unwrap();
+    let err = prg.run_to_halt().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::InputExhausted);
+    assert_eq!(err.instruction_pointer, 2);
+  }
+
+  #[test]
+  fn closure_io() {
+    let mut inputs = vec![3, 4].into_iter();
+    let mut outputs: Vec<i64> = Vec::new();
+    let mut prg = IntcodeProgram::with_io(
+      &[3, 11, 3, 12, 2, 11, 12, 13, 4, 13, 99, 0, 0, 0],
+      InputFn(|| inputs.next()),
+      OutputFn(|value| outputs.push(value)),
+    );
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    drop(prg);
+    assert_eq!(outputs, vec![12]);
+  }
+
+  #[test]
+  fn sparse_memory_backend() {
+    let mut prg = IntcodeProgram::with_memory(
+      SparseMemory::from_image(&[109, 1000, 21101, 3, 4, 0, 204, 0, 99]),
+      VecDeque::new(),
+      Vec::new(),
+    );
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.get_value(1000), 7);
+    assert_eq!(prg.output, vec![7]);
+  }
+
+  #[test]
+  fn decode_cache_far_jump() {
+    // write a halt far beyond the dense limit and jump to it
+    let data = "1101,99,0,1000000000000,1105,1,1000000000000";
+    let mut prg = IntcodeProgram::new(data, vec![]).unwrap();
+    prg.enable_decode_cache();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.instruction_pointer(), 1000000000000);
+  }
+
+  #[test]
+  fn decode_cache_with_self_modifying_code() {
+    // outputs the value at address 13, then patches the first instruction
+    // into an immediate-mode output and loops back once
+    let data = "4,13,1101,0,104,0,1001,14,-1,14,1005,14,0,99,2";
+
+    let mut prg = IntcodeProgram::new(data, vec![]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![99, 13]);
+
+    let mut prg = IntcodeProgram::new(data, vec![]).unwrap();
+    prg.enable_decode_cache();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![99, 13]);
+  }
+
+  #[test]
+  fn quine_program() {
+    let mut prg = IntcodeProgram::new(
+      "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99",
+      vec![],
+    )
+    .unwrap();
+
+    let expected_mem: Vec<i64> = vec![
+      109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+    ];
+    for (i, expected) in expected_mem.iter().enumerate() {
+      assert_eq!(prg.get_value(i), *expected);
+    }
+
+    // program should output its own memory
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, expected_mem);
+  }
+
+  #[test]
+  fn output_16_digit_number() {
+    let mut prg = IntcodeProgram::new("1102,34915192,34915192,7,4,7,99,0", vec![]).unwrap();
+
+    let expected_mem: Vec<i64> = vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0];
+    for (i, expected) in expected_mem.iter().enumerate() {
+      assert_eq!(prg.get_value(i), *expected);
+    }
+
+    // program should output a 16-digit number
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output[0], 1219070632396864);
+  }
+
+  #[test]
+  fn output_middle_number() {
+    let mut prg = IntcodeProgram::new("104,1125899906842624,99", vec![]).unwrap();
+    let expected_mem: Vec<i64> = vec![104, 1125899906842624, 99];
+
+    // program should output the second number in memory
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output[0], expected_mem[1]);
+  }
+
+  #[test]
+  fn instruction_budget() {
+    // count up forever
+    let mut prg = IntcodeProgram::new("1001,7,1,7,1105,1,0,0", vec![]).unwrap();
+    prg.set_instruction_budget(Some(5));
+    assert_eq!(prg.run(), Ok(RunStatus::BudgetExhausted));
+    assert_eq!(prg.instruction_count(), 5);
+    assert_eq!(prg.instruction_budget(), Some(0));
+    assert_eq!(prg.instruction_pointer(), 4);
+    assert_eq!(prg.get_value(7), 3);
+
+    // a new budget resumes where the program stopped
+    prg.set_instruction_budget(Some(2));
+    assert_eq!(prg.run(), Ok(RunStatus::BudgetExhausted));
+    assert_eq!(prg.instruction_pointer(), 4);
+    assert_eq!(prg.get_value(7), 4);
+
+    let err = prg.run_to_halt().unwrap_err();
+    assert_eq!(err.kind, IntcodeErrorKind::BudgetExhausted);
+    assert_eq!(err.instruction_pointer, 4);
+  }
+
+  #[test]
+  fn budget_ignores_input_wait() {
+    let mut prg = IntcodeProgram::new("3,0,99", vec![]).unwrap();
+    prg.set_instruction_budget(Some(2));
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    assert_eq!(prg.instruction_budget(), Some(2));
+    prg.feed(1);
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.instruction_count(), 2);
+  }
+
+  #[test]
+  fn deadline() {
+    let mut prg = IntcodeProgram::new("1105,1,0", vec![]).unwrap();
+    prg.set_deadline(Some(Instant::now()));
+    assert_eq!(prg.run(), Ok(RunStatus::BudgetExhausted));
+    assert!(prg
+      .instruction_count()
+      .is_multiple_of(DEADLINE_CHECK_INTERVAL));
+
+    // a later deadline lets the program continue
+    let count = prg.instruction_count();
+    prg.set_deadline(Some(Instant::now() + std::time::Duration::from_millis(20)));
+    assert_eq!(prg.run(), Ok(RunStatus::BudgetExhausted));
+    assert!(prg.instruction_count() > count);
+  }
+}
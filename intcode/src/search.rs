@@ -0,0 +1,202 @@
+use crate::program::IntcodeProgram;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::vec::Vec;
+
+/// Default number of instructions a single attempt may execute
+pub const DEFAULT_SEARCH_BUDGET: u64 = 1_000_000;
+
+/// Error raised when the number of combinations to search does not fit in
+/// a `usize`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchTooLarge;
+
+impl fmt::Display for SearchTooLarge {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "too many input combinations to search")
+  }
+}
+
+impl Error for SearchTooLarge {}
+
+/// Returns the number of values in a range, or None if it does not fit in a
+/// `usize`
+fn range_len(range: &Range<i64>) -> Option<usize> {
+  if range.end <= range.start {
+    return Some(0);
+  }
+  usize::try_from(range.end.checked_sub(range.start)?).ok()
+}
+
+/// Returns the number of worker threads to use by default: one per
+/// available core, or one if that cannot be determined
+pub fn default_threads() -> usize {
+  thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Brute-force search for the inputs that make a program produce a target
+///
+/// Every combination of values is written to the input addresses of a fresh
+/// copy of the image, the program is run to halt, and the value left at the
+/// result address is compared with the target. Attempts that fail or exceed
+/// the instruction budget are skipped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputSearch {
+  /// Addresses to set and the values to try at each one
+  pub inputs: Vec<(usize, Range<i64>)>,
+  /// Address read after the program halts
+  pub result_address: usize,
+  pub target: i64,
+  /// Most instructions a single attempt may execute
+  pub budget: Option<u64>,
+  /// Number of worker threads; defaults to the number of available cores
+  pub threads: usize,
+}
+
+impl InputSearch {
+  /// Creates a search for the given target at the result address, with no
+  /// inputs yet
+  pub fn new(result_address: usize, target: i64) -> InputSearch {
+    InputSearch {
+      inputs: Vec::new(),
+      result_address,
+      target,
+      budget: Some(DEFAULT_SEARCH_BUDGET),
+      threads: default_threads(),
+    }
+  }
+
+  /// Adds an address to set to each value in the range
+  pub fn input(mut self, address: usize, values: Range<i64>) -> InputSearch {
+    self.inputs.push((address, values));
+    self
+  }
+
+  /// Returns the number of combinations to try, or None if it does not fit
+  /// in a `usize`
+  pub fn combinations(&self) -> Option<usize> {
+    self.inputs.iter().try_fold(1usize, |total, (_, values)| {
+      total.checked_mul(range_len(values)?)
+    })
+  }
+
+  /// Returns the input values of the given combination, varying the last
+  /// input fastest
+  ///
+  /// Only called once `combinations` has checked that every range length
+  /// fits, so the offsets below stay within their ranges.
+  fn values(&self, mut index: usize) -> Vec<i64> {
+    let mut values = vec![0; self.inputs.len()];
+    for (value, (_, range)) in values.iter_mut().zip(self.inputs.iter()).rev() {
+      let len = range_len(range).unwrap_or(1);
+      *value = range.start + (index % len) as i64;
+      index /= len;
+    }
+    values
+  }
+
+  /// Searches the image on all worker threads
+  ///
+  /// Returns the values of the matching inputs in the order they were added.
+  /// When several combinations match, the first in iteration order (the last
+  /// input varying fastest) is returned, regardless of thread scheduling.
+  /// Fails without running anything if there are too many combinations to
+  /// number.
+  pub fn find(&self, image: &[i64]) -> Result<Option<Vec<i64>>, SearchTooLarge> {
+    let total = self.combinations().ok_or(SearchTooLarge)?;
+    let threads = self.threads.clamp(1, total.max(1));
+    let best = AtomicUsize::new(usize::MAX);
+
+    thread::scope(|scope| {
+      for first in 0..threads {
+        let best = &best;
+        scope.spawn(move || {
+          let mut prg = IntcodeProgram::from_image(image, vec![]);
+          let initial = prg.snapshot();
+
+          // threads take interleaved combinations so early matches are found
+          // early; anything past the best match so far can be skipped
+          for index in (first..total).step_by(threads) {
+            if index > best.load(Ordering::Relaxed) {
+              break;
+            }
+            prg.restore(&initial);
+            for ((address, _), value) in self.inputs.iter().zip(self.values(index)) {
+              prg.set_value(*address, value);
+            }
+            prg.set_instruction_budget(self.budget);
+            if prg.run_to_halt().is_ok() && prg.get_value(self.result_address) == self.target {
+              best.fetch_min(index, Ordering::Relaxed);
+              break;
+            }
+          }
+        });
+      }
+    });
+
+    Ok(match best.into_inner() {
+      usize::MAX => None,
+      index => Some(self.values(index)),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn find_sum() {
+    // [0] = [9] + [10]
+    let image = [1, 9, 10, 0, 99, 0, 0, 0, 0, 0, 0];
+    let search = InputSearch::new(0, 12).input(9, 0..10).input(10, 0..10);
+    assert_eq!(search.combinations(), Some(100));
+    assert_eq!(search.find(&image), Ok(Some(vec![3, 9])));
+    assert_eq!(search.find(&[99]), Ok(None));
+  }
+
+  #[test]
+  fn deterministic_across_threads() {
+    // [0] = [9] * [10]; 12 has several factorizations
+    let image = [2, 9, 10, 0, 99, 0, 0, 0, 0, 0, 0];
+    for threads in 1..6 {
+      let mut search = InputSearch::new(0, 12).input(9, -5..13).input(10, 0..13);
+      search.threads = threads;
+      assert_eq!(search.find(&image), Ok(Some(vec![1, 12])));
+    }
+  }
+
+  #[test]
+  fn skip_failing_attempts() {
+    // jt #1, #[2]; add #2, #2, [0]; hlt
+    // jumping to 0 loops forever, 1 and 2 reach an invalid opcode
+    let image = [1105, 1, 0, 1101, 2, 2, 0, 99];
+    let mut search = InputSearch::new(0, 4).input(2, 0..8);
+    search.budget = Some(1000);
+    assert_eq!(search.find(&image), Ok(Some(vec![3])));
+  }
+
+  #[test]
+  fn empty_ranges() {
+    let search = InputSearch::new(0, 0).input(1, 5..5);
+    assert_eq!(search.combinations(), Some(0));
+    assert_eq!(search.find(&[99, 0]), Ok(None));
+  }
+
+  #[test]
+  fn reject_oversized_search() {
+    let search = InputSearch::new(0, 1).input(1, i64::MIN..i64::MAX);
+    assert_eq!(search.combinations(), None);
+    assert_eq!(search.find(&[99, 0]), Err(SearchTooLarge));
+
+    // each range fits, but their product does not
+    let search = InputSearch::new(0, 1)
+      .input(1, 0..1 << 40)
+      .input(2, 0..1 << 40);
+    assert_eq!(search.combinations(), None);
+    assert_eq!(search.find(&[99, 0, 0]), Err(SearchTooLarge));
+  }
+}
@@ -0,0 +1,121 @@
+use crate::error::{IntcodeError, IntcodeErrorKind};
+use crate::program::{IntcodeProgram, RunStatus};
+use std::error::Error;
+use std::fmt;
+use std::vec::Vec;
+
+/// Value output by a self-test program before its final answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Check {
+  /// Address of the output instruction that reported the value
+  pub address: usize,
+  pub value: i64,
+}
+
+/// Outputs of a self-test program, split into checks and the final answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelfTestOutput {
+  /// Every value output before the last
+  pub checks: Vec<Check>,
+  /// Last value output
+  pub value: i64,
+}
+
+/// Error raised while running a self-test program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelfTestError {
+  /// Program failed, or waited for more input than it was given
+  Program(IntcodeError),
+  /// Program halted without output
+  NoOutput,
+}
+
+impl fmt::Display for SelfTestError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SelfTestError::Program(err) => write!(f, "{}", err),
+      SelfTestError::NoOutput => write!(f, "program halted without output"),
+    }
+  }
+}
+
+impl Error for SelfTestError {}
+
+impl From<IntcodeError> for SelfTestError {
+  fn from(err: IntcodeError) -> SelfTestError {
+    SelfTestError::Program(err)
+  }
+}
+
+/// Runs a program that reports checks followed by a final answer, as the
+/// day 5 diagnostic and day 9 BOOST programs do
+///
+/// The program is given the inputs and run to halt. Every output but the last
+/// is a check, recorded with the address of the instruction that output it.
+pub fn run_self_test(image: &[i64], inputs: Vec<i64>) -> Result<SelfTestOutput, SelfTestError> {
+  let mut prg = IntcodeProgram::from_image(image, inputs);
+
+  let mut checks = Vec::new();
+  loop {
+    let address = prg.instruction_pointer();
+    match prg.step()? {
+      RunStatus::Output(value) => checks.push(Check { address, value }),
+      RunStatus::Running => (),
+      RunStatus::Halted => break,
+      RunStatus::NeedsInput => {
+        return Err(
+          prg
+            .fault(IntcodeErrorKind::InputExhausted, None, None)
+            .into(),
+        )
+      }
+      RunStatus::BudgetExhausted => {
+        return Err(
+          prg
+            .fault(IntcodeErrorKind::BudgetExhausted, None, None)
+            .into(),
+        )
+      }
+    }
+  }
+
+  let value = checks.pop().ok_or(SelfTestError::NoOutput)?.value;
+  Ok(SelfTestOutput { checks, value })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn split_checks() {
+    // out #0; in [9]; out [9]; out #7; hlt
+    let image = [104, 0, 3, 9, 4, 9, 104, 7, 99, 0];
+    let output = run_self_test(&image, vec![3]).unwrap();
+    assert_eq!(
+      output.checks,
+      vec![
+        Check {
+          address: 0,
+          value: 0
+        },
+        Check {
+          address: 4,
+          value: 3
+        }
+      ]
+    );
+    assert_eq!(output.value, 7);
+  }
+
+  #[test]
+  fn self_test_errors() {
+    assert_eq!(run_self_test(&[99], vec![]), Err(SelfTestError::NoOutput));
+    match run_self_test(&[3, 0, 3, 0, 99], vec![1]) {
+      Err(SelfTestError::Program(err)) => {
+        assert_eq!(err.kind, IntcodeErrorKind::InputExhausted);
+        assert_eq!(err.instruction_pointer, 2);
+      }
+      result => panic!("unexpected result {:?}", result),
+    }
+  }
+}
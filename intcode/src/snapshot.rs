@@ -0,0 +1,297 @@
+use crate::loader::LoadError;
+use crate::memory::{DenseMemory, Memory};
+use crate::program::IntcodeProgram;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::vec::Vec;
+
+/// Header line identifying a saved snapshot
+const SNAPSHOT_HEADER: &str = "intcode-snapshot 1";
+
+/// Largest run of zero cells kept inside a saved memory segment
+const MAX_SEGMENT_GAP: usize = 16;
+
+/// Complete machine state of an IntcodeProgram at one point in time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot<I = VecDeque<i64>, O = Vec<i64>, M = DenseMemory> {
+  pub memory: M,
+  pub instruction_pointer: usize,
+  pub relative_base: usize,
+  pub halted: bool,
+  /// Input not yet consumed by the program
+  pub input: I,
+  /// Output already emitted by the program
+  pub output: O,
+}
+
+impl<I: Clone, O: Clone, M: Clone> IntcodeProgram<I, O, M> {
+  /// Captures the program's memory, registers, and pending input and output
+  pub fn snapshot(&self) -> Snapshot<I, O, M> {
+    Snapshot {
+      memory: self.memory.clone(),
+      instruction_pointer: self.instruction_pointer,
+      relative_base: self.relative_base,
+      halted: self.halted,
+      input: self.input.clone(),
+      output: self.output.clone(),
+    }
+  }
+
+  /// Returns the program to the state captured in the given snapshot
+  pub fn restore(&mut self, snapshot: &Snapshot<I, O, M>) {
+    self.memory = snapshot.memory.clone();
+    self.instruction_pointer = snapshot.instruction_pointer;
+    self.relative_base = snapshot.relative_base;
+    self.halted = snapshot.halted;
+    self.input = snapshot.input.clone();
+    self.output = snapshot.output.clone();
+
+    // memory may differ from what was decoded
+    if let Some(cache) = self.decode_cache.as_mut() {
+      cache.clear();
+    }
+  }
+}
+
+impl<M: Memory> Snapshot<VecDeque<i64>, Vec<i64>, M> {
+  /// Rebuilds a program from this snapshot
+  pub fn into_program(self) -> IntcodeProgram<VecDeque<i64>, Vec<i64>, M> {
+    let mut prg = IntcodeProgram::with_memory(self.memory, self.input, self.output);
+    prg.instruction_pointer = self.instruction_pointer;
+    prg.relative_base = self.relative_base;
+    prg.halted = self.halted;
+    prg
+  }
+
+  /// Writes the snapshot in its text format
+  ///
+  /// The format is line based: a header, the registers, pending input and
+  /// output, then memory as `memory <start address> <values>` segments.
+  pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+    let mut w = BufWriter::new(writer);
+    writeln!(w, "{}", SNAPSHOT_HEADER)?;
+    writeln!(w, "ip {}", self.instruction_pointer)?;
+    writeln!(w, "rb {}", self.relative_base)?;
+    writeln!(w, "halted {}", if self.halted { 1 } else { 0 })?;
+    writeln!(w, "input {}", join_values(self.input.iter()))?;
+    writeln!(w, "output {}", join_values(self.output.iter()))?;
+
+    // group non-zero cells into segments, keeping short runs of zeros inline
+    let cells = self.memory.cells();
+    let mut i = 0;
+    while i < cells.len() {
+      let start = cells[i].0;
+      let mut end = i;
+      while end + 1 < cells.len() && cells[end + 1].0 - cells[end].0 <= MAX_SEGMENT_GAP {
+        end += 1;
+      }
+      let values: Vec<i64> = (start..=cells[end].0)
+        .map(|addr| self.memory.get(addr))
+        .collect();
+      writeln!(w, "memory {} {}", start, join_values(values.iter()))?;
+      i = end + 1;
+    }
+    w.flush()
+  }
+
+  /// Writes the snapshot to the file at the given path
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    self.write(File::create(path)?)
+  }
+
+  /// Reads a snapshot written by `Snapshot::write`
+  pub fn read<R: Read>(reader: R) -> Result<Self, LoadError> {
+    let mut snapshot = Snapshot {
+      memory: M::from_image(&[]),
+      instruction_pointer: 0,
+      relative_base: 0,
+      halted: false,
+      input: VecDeque::new(),
+      output: Vec::new(),
+    };
+
+    let mut has_header = false;
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+      let line = line?;
+      let line_no = index + 1;
+      let invalid = || LoadError::InvalidSnapshot { line: line_no };
+
+      if !has_header {
+        if line.trim() != SNAPSHOT_HEADER {
+          return Err(invalid());
+        }
+        has_header = true;
+        continue;
+      }
+      if line.trim().is_empty() {
+        continue;
+      }
+
+      let mut parts = line.trim().splitn(2, ' ');
+      let key = parts.next().unwrap_or("");
+      let rest = parts.next().unwrap_or("").trim();
+      match key {
+        "ip" => snapshot.instruction_pointer = parse_address(rest).ok_or_else(invalid)?,
+        "rb" => snapshot.relative_base = parse_address(rest).ok_or_else(invalid)?,
+        "halted" => {
+          snapshot.halted = match rest {
+            "0" => false,
+            "1" => true,
+            _ => return Err(invalid()),
+          }
+        }
+        "input" => snapshot.input = parse_values(rest).ok_or_else(invalid)?.into(),
+        "output" => snapshot.output = parse_values(rest).ok_or_else(invalid)?,
+        "memory" => {
+          let mut segment = rest.splitn(2, ' ');
+          let start = segment.next().and_then(parse_address).ok_or_else(invalid)?;
+          let values = parse_values(segment.next().unwrap_or("")).ok_or_else(invalid)?;
+          for (offset, value) in values.iter().enumerate() {
+            let address = start.checked_add(offset).ok_or_else(invalid)?;
+            snapshot.memory.set(address, *value);
+          }
+        }
+        _ => return Err(invalid()),
+      }
+    }
+
+    if !has_header {
+      return Err(LoadError::Empty);
+    }
+    Ok(snapshot)
+  }
+
+  /// Reads a snapshot from the file at the given path
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+    Snapshot::read(File::open(path)?)
+  }
+}
+
+/// Joins values into a comma-separated list
+fn join_values<'a, T: Iterator<Item = &'a i64>>(values: T) -> String {
+  values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Parses an address, which must be addressable by an i64 operand
+fn parse_address(data: &str) -> Option<usize> {
+  data
+    .parse::<usize>()
+    .ok()
+    .filter(|addr| *addr <= i64::MAX as usize)
+}
+
+/// Parses a comma-separated list of values, which may be empty
+fn parse_values(data: &str) -> Option<Vec<i64>> {
+  if data.is_empty() {
+    return Some(Vec::new());
+  }
+  data.split(',').map(|v| v.trim().parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::memory::SparseMemory;
+  use crate::program::RunStatus;
+  #[test]
+  fn snapshot_and_restore() {
+    // add each input to a running total and output it
+    let mut prg = IntcodeProgram::new("3,11,1,11,12,12,4,12,1105,1,0,0,0", vec![5]).unwrap();
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    let snapshot = prg.snapshot();
+
+    // explore one branch
+    prg.feed(10);
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    assert_eq!(prg.output, vec![5, 15]);
+
+    // and return to try another
+    prg.restore(&snapshot);
+    prg.feed(1);
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    assert_eq!(prg.output, vec![5, 6]);
+  }
+
+  #[test]
+  fn restore_clears_decode_cache() {
+    let mut prg = IntcodeProgram::new("104,1,99", vec![]).unwrap();
+    prg.enable_decode_cache();
+    let snapshot = prg.snapshot();
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+
+    // patch the output instruction, then restore the original
+    prg.set_value(0, 99);
+    prg.restore(&snapshot);
+    prg.set_value(1, 2);
+    assert_eq!(prg.run(), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![2]);
+  }
+
+  #[test]
+  fn save_and_load() {
+    let mut prg = IntcodeProgram::new("109,5,203,100,99", vec![]).unwrap();
+    prg.set_value(2_000_000, -3);
+    assert_eq!(prg.run(), Ok(RunStatus::NeedsInput));
+    prg.feed(8);
+    prg.feed(9);
+    prg.output.push(4);
+    let snapshot = prg.snapshot();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    snapshot.write(&mut buffer).unwrap();
+    let loaded: Snapshot = Snapshot::read(&buffer[..]).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    // resume the loaded program where the original left off
+    let mut resumed = loaded.into_program();
+    assert_eq!(resumed.run(), Ok(RunStatus::Halted));
+    assert_eq!(resumed.get_value(105), 8);
+    assert_eq!(resumed.get_value(2_000_000), -3);
+    assert_eq!(resumed.input, vec![9]);
+    assert_eq!(resumed.output, vec![4]);
+
+    // any memory backend can load a snapshot
+    let sparse: Snapshot<VecDeque<i64>, Vec<i64>, SparseMemory> =
+      Snapshot::read(&buffer[..]).unwrap();
+    assert_eq!(sparse.memory.get(2_000_000), -3);
+  }
+
+  #[test]
+  fn load_invalid_snapshot() {
+    let data: &[u8] = b"intcode-snapshot 1\nip 0\nrb x\n";
+    match Snapshot::<VecDeque<i64>, Vec<i64>, DenseMemory>::read(data) {
+      Err(LoadError::InvalidSnapshot { line: 3 }) => (),
+      other => panic!("unexpected result: {:?}", other),
+    }
+
+    let data: &[u8] = b"1,2,3\n";
+    match Snapshot::<VecDeque<i64>, Vec<i64>, DenseMemory>::read(data) {
+      Err(LoadError::InvalidSnapshot { line: 1 }) => (),
+      other => panic!("unexpected result: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn load_malformed_fields() {
+    let cases: [&[u8]; 5] = [
+      b"intcode-snapshot 1\nmemory 18446744073709551615 1,2\n",
+      b"intcode-snapshot 1\nmemory 9223372036854775808 1\n",
+      b"intcode-snapshot 1\nip 9223372036854775808\n",
+      b"intcode-snapshot 1\nrb 18446744073709551615\n",
+      b"intcode-snapshot 1\nhalted yes\n",
+    ];
+    for data in cases.iter() {
+      match Snapshot::<VecDeque<i64>, Vec<i64>, DenseMemory>::read(*data) {
+        Err(LoadError::InvalidSnapshot { line: 2 }) => (),
+        other => panic!("unexpected result: {:?}", other),
+      }
+    }
+
+    let data: &[u8] = b"intcode-snapshot 1\nrb 9223372036854775807\nhalted 0\n";
+    let snapshot = Snapshot::<VecDeque<i64>, Vec<i64>, DenseMemory>::read(data).unwrap();
+    assert_eq!(snapshot.relative_base, i64::MAX as usize);
+    assert!(!snapshot.halted);
+  }
+}
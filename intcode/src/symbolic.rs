@@ -0,0 +1,511 @@
+//! Symbolic execution of straight-line Intcode
+//!
+//! Chosen input addresses are treated as variables and every memory cell is
+//! tracked as a polynomial in those variables. Only add (1), multiply (2) and
+//! halt (99) are supported, in position or immediate mode, so the program
+//! must run straight through without data-dependent jumps.
+//!
+//! Values read through an address that depends on the inputs cannot be
+//! tracked and become unknown. That is fine as long as they are overwritten
+//! before they reach the result, as happens in day 2 where the first
+//! instruction reads the cells at the noun and verb addresses.
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::vec::Vec;
+
+/// Polynomial with integer coefficients in numbered variables
+///
+/// Each term is keyed by the sorted list of variables it multiplies, with
+/// repeats for powers: `x0^2 * x1` is `[0, 0, 1]` and the constant is `[]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Polynomial {
+  terms: BTreeMap<Vec<usize>, i64>,
+}
+
+impl Polynomial {
+  /// Returns the constant polynomial with the given value
+  pub fn constant(value: i64) -> Polynomial {
+    let mut terms = BTreeMap::new();
+    if value != 0 {
+      terms.insert(Vec::new(), value);
+    }
+    Polynomial { terms }
+  }
+
+  /// Returns the polynomial made of a single variable
+  pub fn variable(index: usize) -> Polynomial {
+    let mut terms = BTreeMap::new();
+    terms.insert(vec![index], 1);
+    Polynomial { terms }
+  }
+
+  /// Returns the non-zero terms as variable lists and coefficients
+  pub fn terms(&self) -> impl Iterator<Item = (&[usize], i64)> {
+    self.terms.iter().map(|(vars, c)| (vars.as_slice(), *c))
+  }
+
+  /// Returns the value of the polynomial if it does not depend on any variable
+  pub fn as_constant(&self) -> Option<i64> {
+    match self.terms.len() {
+      0 => Some(0),
+      1 => self.terms.get(&Vec::new()).copied(),
+      _ => None,
+    }
+  }
+
+  /// Returns the highest number of variables multiplied in a single term
+  pub fn degree(&self) -> usize {
+    self.terms.keys().map(|vars| vars.len()).max().unwrap_or(0)
+  }
+
+  /// Adds a multiple of a term, dropping it if the coefficient cancels out
+  fn add_term(&mut self, vars: Vec<usize>, coefficient: i64) -> Option<()> {
+    let c = self.terms.entry(vars).or_insert(0);
+    *c = c.checked_add(coefficient)?;
+    self.terms.retain(|_, c| *c != 0);
+    Some(())
+  }
+
+  /// Returns the sum, or None if a coefficient overflows
+  pub fn checked_add(&self, other: &Polynomial) -> Option<Polynomial> {
+    let mut sum = self.clone();
+    for (vars, c) in other.terms.iter() {
+      sum.add_term(vars.clone(), *c)?;
+    }
+    Some(sum)
+  }
+
+  /// Returns the product, or None if a coefficient overflows
+  pub fn checked_mul(&self, other: &Polynomial) -> Option<Polynomial> {
+    let mut product = Polynomial::default();
+    for (l_vars, l_c) in self.terms.iter() {
+      for (r_vars, r_c) in other.terms.iter() {
+        let mut vars: Vec<usize> = l_vars.iter().chain(r_vars.iter()).copied().collect();
+        vars.sort_unstable();
+        product.add_term(vars, l_c.checked_mul(*r_c)?)?;
+      }
+    }
+    Some(product)
+  }
+
+  /// Evaluates the polynomial with the given variable values, or returns None
+  /// if a variable has no value or the result overflows
+  pub fn evaluate(&self, values: &[i64]) -> Option<i64> {
+    self.terms.iter().try_fold(0i64, |sum, (vars, c)| {
+      let term = vars
+        .iter()
+        .try_fold(*c, |p, v| p.checked_mul(*values.get(*v)?))?;
+      sum.checked_add(term)
+    })
+  }
+
+  /// Returns the polynomial in the last variable, `a * x^k + ...`, as the
+  /// value of each coefficient `a` indexed by power `k`, with all other
+  /// variables given by `values`
+  fn collect_last(&self, values: &[i64], last: usize) -> Option<Vec<i64>> {
+    let mut coefficients: Vec<i64> = Vec::new();
+    for (vars, c) in self.terms.iter() {
+      let power = vars.iter().filter(|v| **v == last).count();
+      let term = vars
+        .iter()
+        .filter(|v| **v != last)
+        .try_fold(*c, |p, v| p.checked_mul(values[*v]))?;
+      if coefficients.len() <= power {
+        coefficients.resize(power + 1, 0);
+      }
+      coefficients[power] = coefficients[power].checked_add(term)?;
+    }
+    Some(coefficients)
+  }
+}
+
+/// Cause of a failure to execute a program symbolically
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SymbolicErrorKind {
+  /// Opcode or mode depends on the inputs
+  SymbolicOpcode,
+  /// Instruction is not add, multiply or halt
+  UnsupportedOpcode(i64),
+  /// Parameter mode is not position or immediate
+  InvalidMode(i64),
+  /// Address written by the given parameter depends on the inputs
+  SymbolicWrite(usize),
+  /// Attempted to access a negative address
+  NegativeAddress(i64),
+  /// A coefficient does not fit in a 64-bit integer
+  Overflow,
+  /// Result was read through an address that depends on the inputs
+  UnknownResult,
+}
+
+/// Error raised while executing a program symbolically
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolicError {
+  /// Address of the failing instruction, or of the result for `UnknownResult`
+  pub address: usize,
+  /// Cause of the failure
+  pub kind: SymbolicErrorKind,
+}
+
+impl fmt::Display for SymbolicErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SymbolicErrorKind::SymbolicOpcode => write!(f, "opcode depends on the inputs"),
+      SymbolicErrorKind::UnsupportedOpcode(op) => write!(f, "unsupported opcode {}", op),
+      SymbolicErrorKind::InvalidMode(mode) => write!(f, "invalid mode {}", mode),
+      SymbolicErrorKind::SymbolicWrite(param) => {
+        write!(
+          f,
+          "address written by parameter {} depends on the inputs",
+          param
+        )
+      }
+      SymbolicErrorKind::NegativeAddress(addr) => write!(f, "negative address {}", addr),
+      SymbolicErrorKind::Overflow => write!(f, "coefficient overflow"),
+      SymbolicErrorKind::UnknownResult => {
+        write!(
+          f,
+          "result read through an address that depends on the inputs"
+        )
+      }
+    }
+  }
+}
+
+impl fmt::Display for SymbolicError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} at address {}", self.kind, self.address)
+  }
+}
+
+impl Error for SymbolicError {}
+
+/// Value of a memory cell as a polynomial in the input cells
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClosedForm {
+  /// Input addresses; variable `i` is the value at `inputs[i]`
+  pub inputs: Vec<usize>,
+  pub polynomial: Polynomial,
+}
+
+impl ClosedForm {
+  /// Evaluates the closed form with the given input values, or returns None
+  /// if the number of values does not match the inputs or the result
+  /// overflows
+  pub fn evaluate(&self, values: &[i64]) -> Option<i64> {
+    if values.len() != self.inputs.len() {
+      return None;
+    }
+    self.polynomial.evaluate(values)
+  }
+
+  /// Finds input values within the given ranges for which the closed form
+  /// equals the target
+  ///
+  /// Returns the first solution with the last input varying fastest. All
+  /// inputs but the last are enumerated; the last is solved for directly
+  /// when the polynomial is linear in it. Returns None if there is not
+  /// exactly one range per input.
+  pub fn solve(&self, target: i64, ranges: &[Range<i64>]) -> Option<Vec<i64>> {
+    if ranges.len() != self.inputs.len() {
+      return None;
+    }
+    let last = match ranges.len() {
+      0 => return Some(Vec::new()).filter(|_| self.evaluate(&[]) == Some(target)),
+      len => len - 1,
+    };
+    if ranges.iter().any(|r| r.start >= r.end) {
+      return None;
+    }
+
+    let mut values: Vec<i64> = ranges.iter().map(|r| r.start).collect();
+    loop {
+      if let Some(x) = self.solve_last(target, &values, &ranges[last]) {
+        values[last] = x;
+        return Some(values);
+      }
+
+      // advance the other inputs like an odometer
+      let mut i = last;
+      loop {
+        if i == 0 {
+          return None;
+        }
+        i -= 1;
+        values[i] += 1;
+        if values[i] < ranges[i].end {
+          break;
+        }
+        values[i] = ranges[i].start;
+      }
+    }
+  }
+
+  /// Returns the smallest value of the last input in range that reaches the
+  /// target, with the other inputs fixed
+  fn solve_last(&self, target: i64, values: &[i64], range: &Range<i64>) -> Option<i64> {
+    let last = values.len() - 1;
+    let coefficients = self.polynomial.collect_last(values, last)?;
+    match coefficients.as_slice() {
+      [] => Some(range.start).filter(|_| target == 0),
+      [b] => Some(range.start).filter(|_| *b == target),
+      [b, a] => {
+        // a * x + b = target
+        let rest = target.checked_sub(*b)?;
+        if *a == 0 {
+          Some(range.start).filter(|_| rest == 0)
+        } else {
+          // i64::MIN / -1 does not fit, so has no solution either
+          let x = rest.checked_div(*a)?;
+          Some(x).filter(|x| rest.checked_rem(*a) == Some(0) && range.contains(x))
+        }
+      }
+      _ => {
+        let mut values = values.to_vec();
+        range.clone().find(|x| {
+          values[last] = *x;
+          self.evaluate(&values) == Some(target)
+        })
+      }
+    }
+  }
+}
+
+impl fmt::Display for ClosedForm {
+  /// Writes the polynomial with variables named by their address, highest
+  /// degree first: `3*[1]^2 - [2] + 7`
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut terms: Vec<(&[usize], i64)> = self.polynomial.terms().collect();
+    terms.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(b.0)));
+    if terms.is_empty() {
+      return write!(f, "0");
+    }
+
+    for (i, (vars, c)) in terms.iter().enumerate() {
+      let sign = if *c < 0 { "-" } else { "+" };
+      match i {
+        0 if *c < 0 => write!(f, "-")?,
+        0 => (),
+        _ => write!(f, " {} ", sign)?,
+      }
+
+      let mut factors = Vec::new();
+      if c.unsigned_abs() != 1 || vars.is_empty() {
+        factors.push(c.unsigned_abs().to_string());
+      }
+      let mut j = 0;
+      while j < vars.len() {
+        let power = vars[j..].iter().take_while(|v| **v == vars[j]).count();
+        let name = format!("[{}]", self.inputs[vars[j]]);
+        factors.push(match power {
+          1 => name,
+          _ => format!("{}^{}", name, power),
+        });
+        j += power;
+      }
+      write!(f, "{}", factors.join("*"))?;
+    }
+    Ok(())
+  }
+}
+
+/// Reads the address of a position mode parameter, or None if it depends on
+/// the inputs
+fn position(cell: &Option<Polynomial>) -> Result<Option<usize>, SymbolicErrorKind> {
+  match cell.as_ref().and_then(|p| p.as_constant()) {
+    Some(addr) if addr < 0 => Err(SymbolicErrorKind::NegativeAddress(addr)),
+    Some(addr) => Ok(Some(addr as usize)),
+    None => Ok(None),
+  }
+}
+
+/// Executes the image from address 0 until it halts, with the cells at the
+/// given addresses as variables, and returns the closed form of the cell at
+/// the result address
+pub fn closed_form(
+  image: &[i64],
+  inputs: &[usize],
+  result: usize,
+) -> Result<ClosedForm, SymbolicError> {
+  // cells are kept sparsely so far addresses cost nothing; missing cells are
+  // zero and None marks a cell whose value could not be tracked
+  let mut memory: BTreeMap<usize, Option<Polynomial>> = image
+    .iter()
+    .enumerate()
+    .map(|(addr, v)| (addr, Some(Polynomial::constant(*v))))
+    .collect();
+  for (i, addr) in inputs.iter().enumerate() {
+    memory.insert(*addr, Some(Polynomial::variable(i)));
+  }
+
+  let zero = Some(Polynomial::default());
+  let mut ip = 0;
+  loop {
+    let fail = |kind| SymbolicError { address: ip, kind };
+    let cell = |addr: usize| memory.get(&addr).unwrap_or(&zero).clone();
+    let instruction = cell(ip)
+      .and_then(|p| p.as_constant())
+      .ok_or_else(|| fail(SymbolicErrorKind::SymbolicOpcode))?;
+    match instruction % 100 {
+      99 => break,
+      1 | 2 => (),
+      _ => return Err(fail(SymbolicErrorKind::UnsupportedOpcode(instruction))),
+    }
+
+    let mut params = Vec::new();
+    for i in 0..3 {
+      let mode = instruction / 10i64.pow(i as u32 + 2) % 10;
+      let param = cell(ip + 1 + i);
+      params.push(match (mode, i) {
+        (0, 2) => match position(&param).map_err(fail)? {
+          Some(addr) => Some(Polynomial::constant(addr as i64)),
+          None => return Err(fail(SymbolicErrorKind::SymbolicWrite(i))),
+        },
+        (0, _) => match position(&param).map_err(fail)? {
+          Some(addr) => cell(addr),
+          None => None,
+        },
+        (1, 2) => return Err(fail(SymbolicErrorKind::InvalidMode(mode))),
+        (1, _) => param,
+        _ => return Err(fail(SymbolicErrorKind::InvalidMode(mode))),
+      });
+    }
+
+    let value = match (&params[0], &params[1]) {
+      (Some(l), Some(r)) if instruction % 100 == 1 => Some(
+        l.checked_add(r)
+          .ok_or_else(|| fail(SymbolicErrorKind::Overflow))?,
+      ),
+      (Some(l), Some(r)) => Some(
+        l.checked_mul(r)
+          .ok_or_else(|| fail(SymbolicErrorKind::Overflow))?,
+      ),
+      _ => None,
+    };
+    let target = params[2]
+      .as_ref()
+      .and_then(|p| p.as_constant())
+      .unwrap_or(0) as usize;
+    memory.insert(target, value);
+    ip += 4;
+  }
+
+  match memory.get(&result).unwrap_or(&zero) {
+    Some(polynomial) => Ok(ClosedForm {
+      inputs: inputs.to_vec(),
+      polynomial: polynomial.clone(),
+    }),
+    None => Err(SymbolicError {
+      address: result,
+      kind: SymbolicErrorKind::UnknownResult,
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::program::IntcodeProgram;
+  #[test]
+  fn linear_closed_form() {
+    // [3] = [n] + [v] is discarded; [3] = n + v; [0] = [3] * 5 + 1
+    let image = [1, 0, 0, 3, 1, 1, 2, 3, 1002, 3, 5, 0, 101, 1, 0, 0, 99];
+    let form = closed_form(&image, &[1, 2], 0).unwrap();
+    assert_eq!(form.to_string(), "5*[1] + 5*[2] + 1");
+    assert_eq!(form.polynomial.degree(), 1);
+
+    let pair = form.solve(36, &[0..10, 0..10]).unwrap();
+    assert_eq!(pair, vec![0, 7]);
+    assert_eq!(form.solve(37, &[0..10, 0..10]), None);
+
+    // the closed form agrees with running the program
+    let mut prg = IntcodeProgram::from_image(&image, vec![]);
+    prg.set_value(1, 4);
+    prg.set_value(2, 3);
+    prg.run_to_halt().unwrap();
+    assert_eq!(Some(prg.get_value(0)), form.evaluate(&[4, 3]));
+  }
+
+  #[test]
+  fn quadratic_closed_form() {
+    // [0] = [1] + [2]; [0] = [0] * [0] - [2]
+    let image = [
+      1, 0, 0, 3, 1, 1, 2, 0, 2, 0, 0, 0, 1002, 2, -1, 2, 1, 0, 2, 0, 99,
+    ];
+    let form = closed_form(&image, &[1, 2], 0).unwrap();
+    assert_eq!(form.to_string(), "[1]^2 + 2*[1]*[2] + [2]^2 - [2]");
+    assert_eq!(form.polynomial.degree(), 2);
+    assert_eq!(form.solve(12, &[0..10, 0..10]), Some(vec![0, 4]));
+    assert_eq!(form.solve(14, &[2..10, 0..10]), Some(vec![2, 2]));
+    assert_eq!(form.solve(13, &[2..10, 0..10]), None);
+  }
+
+  #[test]
+  fn symbolic_errors() {
+    let err = closed_form(&[1105, 1, 0, 99], &[], 0).unwrap_err();
+    assert_eq!(err.kind, SymbolicErrorKind::UnsupportedOpcode(1105));
+
+    // write through an input address
+    let err = closed_form(&[1101, 1, 1, 9, 99], &[3], 0).unwrap_err();
+    assert_eq!(err.kind, SymbolicErrorKind::SymbolicWrite(2));
+
+    // read through an input address into the result
+    let err = closed_form(&[1, 5, 6, 0, 99, 0, 0], &[1], 0).unwrap_err();
+    assert_eq!(err.address, 0);
+    assert_eq!(err.kind, SymbolicErrorKind::UnknownResult);
+
+    // an input replacing an opcode
+    let err = closed_form(&[1101, 1, 1, 0, 99], &[4], 0).unwrap_err();
+    assert_eq!(err.address, 4);
+    assert_eq!(err.kind, SymbolicErrorKind::SymbolicOpcode);
+  }
+
+  #[test]
+  fn far_addresses() {
+    // a far write, and a far input read back into the result
+    let form = closed_form(&[1101, 1, 1, 1000000000000, 99], &[], 0).unwrap();
+    assert_eq!(form.polynomial.as_constant(), Some(1101));
+
+    let image = [1, 1000000000000, 9, 0, 99, 0, 0, 0, 0, 7];
+    let form = closed_form(&image, &[1000000000000], 0).unwrap();
+    assert_eq!(form.to_string(), "[1000000000000] + 7");
+  }
+
+  #[test]
+  fn solve_edge_cases() {
+    // [0] = -[5]; the target i64::MIN needs [5] = i64::MIN / -1, which overflows
+    let form = closed_form(&[102, -1, 5, 0, 99, 0], &[5], 0).unwrap();
+    let range = 0..10;
+    let ranges = std::slice::from_ref(&range);
+    assert_eq!(form.solve(i64::MIN, ranges), None);
+    assert_eq!(form.solve(-3, ranges), Some(vec![3]));
+
+    // one range per input is required
+    assert_eq!(form.solve(-3, &[]), None);
+    assert_eq!(form.solve(-3, &[0..10, 0..10]), None);
+    assert_eq!(form.evaluate(&[]), None);
+  }
+
+  #[test]
+  fn polynomial_arithmetic() {
+    let x = Polynomial::variable(0);
+    let y = Polynomial::variable(1);
+    let sum = x.checked_add(&Polynomial::constant(-3)).unwrap();
+    let product = sum.checked_mul(&y).unwrap();
+    assert_eq!(product.evaluate(&[5, 7]), Some(14));
+    assert_eq!(product.as_constant(), None);
+    assert_eq!(
+      sum.checked_add(&Polynomial::constant(3)).unwrap(),
+      Polynomial::variable(0)
+    );
+    assert_eq!(
+      x.checked_mul(&Polynomial::default()).unwrap().as_constant(),
+      Some(0)
+    );
+
+    let big = Polynomial::constant(i64::MAX);
+    assert_eq!(big.checked_add(&Polynomial::constant(1)), None);
+  }
+}
@@ -0,0 +1,175 @@
+use crate::error::IntcodeError;
+use crate::io::InputSource;
+use crate::memory::{DenseMemory, Memory};
+use crate::program::{IntcodeProgram, RunStatus};
+use std::collections::VecDeque;
+use std::panic;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::vec::Vec;
+
+/// Input already queued on the program, followed by values from a channel
+struct QueuedChannel {
+  queued: VecDeque<i64>,
+  channel: Receiver<i64>,
+}
+
+impl InputSource for QueuedChannel {
+  fn read(&mut self) -> Option<i64> {
+    match self.queued.pop_front() {
+      Some(value) => Some(value),
+      None => self.channel.recv().ok(),
+    }
+  }
+}
+
+/// Final machine and run result of a ProgramThread
+type ThreadResult<M> = (
+  IntcodeProgram<VecDeque<i64>, Vec<i64>, M>,
+  Result<RunStatus, IntcodeError>,
+);
+
+/// Intcode program running on its own thread, connected by channels
+///
+/// The program first consumes any input already queued on it, then blocks on
+/// its input channel. It runs until it halts, fails, runs out of budget, or
+/// needs input after every sender to its input channel has been dropped.
+/// Its output channel is closed when it stops.
+#[derive(Debug)]
+pub struct ProgramThread<M = DenseMemory> {
+  handle: JoinHandle<ThreadResult<M>>,
+}
+
+impl<M: Memory + Send + 'static> ProgramThread<M> {
+  /// Starts the program on a new thread reading from `input` and writing to `output`
+  ///
+  /// Output already collected on the program stays on the final machine.
+  pub fn spawn(
+    mut program: IntcodeProgram<VecDeque<i64>, Vec<i64>, M>,
+    input: Receiver<i64>,
+    output: Sender<i64>,
+  ) -> ProgramThread<M> {
+    let handle = thread::spawn(move || {
+      let queued = std::mem::take(&mut program.input);
+      let (mut prg, _, collected) = program.replace_io(
+        QueuedChannel {
+          queued,
+          channel: input,
+        },
+        output,
+      );
+      let status = prg.run();
+
+      // hand back a regular machine holding any input left unread; dropping
+      // the sender closes the output channel
+      let (mut prg, input, _) = prg.replace_io(VecDeque::new(), collected);
+      prg.input = input.queued;
+      prg.input.extend(input.channel.try_iter());
+      (prg, status)
+    });
+    ProgramThread { handle }
+  }
+
+  /// Starts the program on a new thread with fresh channels
+  ///
+  /// Returns the thread, the sender feeding its input, and the receiver of
+  /// its output.
+  pub fn start(
+    program: IntcodeProgram<VecDeque<i64>, Vec<i64>, M>,
+  ) -> (ProgramThread<M>, Sender<i64>, Receiver<i64>) {
+    let (input_tx, input_rx) = mpsc::channel();
+    let (output_tx, output_rx) = mpsc::channel();
+    (
+      ProgramThread::spawn(program, input_rx, output_tx),
+      input_tx,
+      output_rx,
+    )
+  }
+
+  /// Waits for the program to stop and returns the final machine with the
+  /// result of its run
+  ///
+  /// Unread input is left queued on the returned machine.
+  pub fn join(self) -> ThreadResult<M> {
+    match self.handle.join() {
+      Ok(result) => result,
+      Err(err) => panic::resume_unwind(err),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn echo_over_channels() {
+    // add one to each input until it reads 0
+    let source = "3,15,1006,15,14,101,1,15,16,4,16,1105,1,0,99,0,0";
+    let (runner, tx, rx) = ProgramThread::start(IntcodeProgram::new(source, vec![]).unwrap());
+
+    tx.send(4).unwrap();
+    assert_eq!(rx.recv(), Ok(5));
+    tx.send(-2).unwrap();
+    assert_eq!(rx.recv(), Ok(-1));
+    tx.send(0).unwrap();
+
+    let (prg, status) = runner.join();
+    assert_eq!(status, Ok(RunStatus::Halted));
+    assert!(prg.is_halted());
+    assert_eq!(prg.get_value(16), -1);
+
+    // the output channel closes once the program stops
+    assert_eq!(rx.recv(), Err(mpsc::RecvError));
+  }
+
+  #[test]
+  fn closed_input_leaves_machine_resumable() {
+    let mut prg = IntcodeProgram::new("3,9,3,10,4,10,99,0,0,0,0", vec![7]).unwrap();
+    prg.output.push(1);
+    let (runner, tx, rx) = ProgramThread::start(prg);
+    drop(tx);
+
+    let (mut prg, status) = runner.join();
+    assert_eq!(status, Ok(RunStatus::NeedsInput));
+    assert_eq!(prg.instruction_pointer(), 2);
+    assert_eq!(prg.get_value(9), 7);
+    assert_eq!(rx.recv(), Err(mpsc::RecvError));
+
+    // continue on the calling thread
+    prg.feed(8);
+    prg.run_to_halt().unwrap();
+    assert_eq!(prg.output, vec![1, 8]);
+  }
+
+  #[test]
+  fn feedback_loop() {
+    // day 7 sample: five amplifiers wired in a ring, phases 9,8,7,6,5
+    let source =
+      "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+    let amp = |phase: i64| IntcodeProgram::new(source, vec![phase]).unwrap();
+
+    // chain the first four amplifiers, each reading the previous one's output
+    let (first_tx, mut input) = mpsc::channel();
+    let mut runners = Vec::new();
+    for phase in [9, 8, 7, 6].iter() {
+      let (tx, rx) = mpsc::channel();
+      runners.push(ProgramThread::spawn(amp(*phase), input, tx));
+      input = rx;
+    }
+    let (tap_tx, tap_rx) = mpsc::channel();
+    runners.push(ProgramThread::spawn(amp(5), input, tap_tx));
+
+    // feed the last amplifier's output back to the first until it stops
+    first_tx.send(0).unwrap();
+    let mut signal = None;
+    for value in tap_rx {
+      signal = Some(value);
+      let _ = first_tx.send(value);
+    }
+    assert_eq!(signal, Some(139629729));
+
+    for runner in runners {
+      assert_eq!(runner.join().1, Ok(RunStatus::Halted));
+    }
+  }
+}
@@ -0,0 +1,242 @@
+use crate::error::IntcodeError;
+use crate::io::{InputSource, OutputSink};
+use crate::memory::Memory;
+use crate::program::{IntcodeProgram, RunStatus};
+use std::io::{self, Write};
+use std::vec::Vec;
+
+/// Record of a single executed instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEvent {
+  /// Address of the instruction
+  pub ip: usize,
+  pub opcode: u8,
+  /// Mode of each parameter
+  pub modes: Vec<u8>,
+  /// Resolved parameters: the value read for read parameters, the target
+  /// address for write parameters
+  pub operands: Vec<i64>,
+  /// Addresses read by the read parameters
+  pub reads: Vec<usize>,
+  /// Address and value of the memory write performed, if any
+  pub write: Option<(usize, i64)>,
+  /// Relative base after the instruction
+  pub relative_base: usize,
+}
+
+/// Receives a record of every instruction executed by a traced run
+pub trait Tracer {
+  fn record(&mut self, event: &TraceEvent);
+}
+
+/// Keeps every event in memory
+impl Tracer for Vec<TraceEvent> {
+  fn record(&mut self, event: &TraceEvent) {
+    self.push(event.clone());
+  }
+}
+
+/// Text format written by a TraceWriter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+  /// One JSON object per line
+  JsonLines,
+  /// Comma-separated values with a header row; lists are space-separated
+  Csv,
+}
+
+/// Writes trace events to any writer in a machine-readable format
+///
+/// The first write error stops tracing and is returned by `finish`.
+#[derive(Debug)]
+pub struct TraceWriter<W: Write> {
+  writer: W,
+  format: TraceFormat,
+  header_written: bool,
+  error: Option<io::Error>,
+}
+
+impl<W: Write> TraceWriter<W> {
+  /// Creates a tracer writing events to the given writer
+  pub fn new(writer: W, format: TraceFormat) -> TraceWriter<W> {
+    TraceWriter {
+      writer,
+      format,
+      header_written: false,
+      error: None,
+    }
+  }
+
+  /// Flushes the writer and returns it, or the first error met while tracing
+  pub fn finish(mut self) -> io::Result<W> {
+    if let Some(err) = self.error.take() {
+      return Err(err);
+    }
+    self.writer.flush()?;
+    Ok(self.writer)
+  }
+
+  /// Writes one event in the configured format
+  fn write_event(&mut self, event: &TraceEvent) -> io::Result<()> {
+    let modes: Vec<String> = event.modes.iter().map(|m| m.to_string()).collect();
+    let operands: Vec<String> = event.operands.iter().map(|v| v.to_string()).collect();
+    match self.format {
+      TraceFormat::JsonLines => {
+        let write = match event.write {
+          Some((addr, value)) => format!("{{\"addr\":{},\"value\":{}}}", addr, value),
+          None => "null".to_owned(),
+        };
+        writeln!(
+          self.writer,
+          "{{\"ip\":{},\"opcode\":{},\"modes\":[{}],\"operands\":[{}],\"write\":{},\"relative_base\":{}}}",
+          event.ip,
+          event.opcode,
+          modes.join(","),
+          operands.join(","),
+          write,
+          event.relative_base
+        )
+      }
+      TraceFormat::Csv => {
+        if !self.header_written {
+          writeln!(
+            self.writer,
+            "ip,opcode,modes,operands,write_addr,write_value,relative_base"
+          )?;
+          self.header_written = true;
+        }
+        let (addr, value) = match event.write {
+          Some((addr, value)) => (addr.to_string(), value.to_string()),
+          None => (String::new(), String::new()),
+        };
+        writeln!(
+          self.writer,
+          "{},{},{},{},{},{},{}",
+          event.ip,
+          event.opcode,
+          modes.join(" "),
+          operands.join(" "),
+          addr,
+          value,
+          event.relative_base
+        )
+      }
+    }
+  }
+}
+
+impl<W: Write> Tracer for TraceWriter<W> {
+  fn record(&mut self, event: &TraceEvent) {
+    if self.error.is_none() {
+      if let Err(err) = self.write_event(event) {
+        self.error = Some(err);
+      }
+    }
+  }
+}
+
+impl<I: InputSource, O: OutputSink, M: Memory> IntcodeProgram<I, O, M> {
+  /// Executes a single instruction, passing a record of it to the tracer
+  ///
+  /// Nothing is recorded for instructions that fail or wait for input.
+  pub fn step_traced(&mut self, tracer: &mut dyn Tracer) -> Result<RunStatus, IntcodeError> {
+    self.step_with(Some(tracer))
+  }
+
+  /// Executes the IntcodeProgram until it halts or needs more input,
+  /// passing a record of every instruction to the tracer
+  pub fn run_traced(&mut self, tracer: &mut dyn Tracer) -> Result<RunStatus, IntcodeError> {
+    loop {
+      match self.step_with(Some(&mut *tracer))? {
+        RunStatus::Running | RunStatus::Output(_) => continue,
+        status => return Ok(status),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn record_events() {
+    let mut prg = IntcodeProgram::new("109,3,21101,2,3,1,204,1,99", vec![]).unwrap();
+    let mut events: Vec<TraceEvent> = Vec::new();
+    assert_eq!(prg.run_traced(&mut events), Ok(RunStatus::Halted));
+    assert_eq!(prg.output, vec![5]);
+
+    assert_eq!(events.len(), 4);
+    assert_eq!(
+      events[0],
+      TraceEvent {
+        ip: 0,
+        opcode: 9,
+        modes: vec![1],
+        operands: vec![3],
+        reads: vec![1],
+        write: None,
+        relative_base: 3,
+      }
+    );
+    assert_eq!(
+      events[1],
+      TraceEvent {
+        ip: 2,
+        opcode: 1,
+        modes: vec![1, 1, 2],
+        operands: vec![2, 3, 4],
+        reads: vec![3, 4],
+        write: Some((4, 5)),
+        relative_base: 3,
+      }
+    );
+    assert_eq!(events[2].operands, vec![5]);
+    assert_eq!(events[2].reads, vec![4]);
+    assert_eq!(events[3].opcode, 99);
+    assert!(events[3].operands.is_empty());
+  }
+
+  #[test]
+  fn skip_input_wait() {
+    let mut prg = IntcodeProgram::new("3,0,99", vec![]).unwrap();
+    let mut events: Vec<TraceEvent> = Vec::new();
+    assert_eq!(prg.run_traced(&mut events), Ok(RunStatus::NeedsInput));
+    assert!(events.is_empty());
+
+    prg.feed(7);
+    assert_eq!(prg.step_traced(&mut events), Ok(RunStatus::Running));
+    assert_eq!(events[0].write, Some((0, 7)));
+  }
+
+  #[test]
+  fn write_json_lines() {
+    let mut prg = IntcodeProgram::new("1101,2,3,0,104,7,99", vec![]).unwrap();
+    let mut tracer = TraceWriter::new(Vec::new(), TraceFormat::JsonLines);
+    prg.run_traced(&mut tracer).unwrap();
+    let text = String::from_utf8(tracer.finish().unwrap()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+      lines,
+      vec![
+        r#"{"ip":0,"opcode":1,"modes":[1,1,0],"operands":[2,3,0],"write":{"addr":0,"value":5},"relative_base":0}"#,
+        r#"{"ip":4,"opcode":4,"modes":[1],"operands":[7],"write":null,"relative_base":0}"#,
+        r#"{"ip":6,"opcode":99,"modes":[],"operands":[],"write":null,"relative_base":0}"#,
+      ]
+    );
+  }
+
+  #[test]
+  fn write_csv() {
+    let mut prg = IntcodeProgram::new("1101,2,3,0,104,7,99", vec![]).unwrap();
+    let mut tracer = TraceWriter::new(Vec::new(), TraceFormat::Csv);
+    prg.run_traced(&mut tracer).unwrap();
+    let text = String::from_utf8(tracer.finish().unwrap()).unwrap();
+    assert_eq!(
+      text,
+      "ip,opcode,modes,operands,write_addr,write_value,relative_base\n\
+       0,1,1 1 0,2 3 0,0,5,0\n\
+       4,4,1,7,,,0\n\
+       6,99,,,,,0\n"
+    );
+  }
+}